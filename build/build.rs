@@ -51,6 +51,9 @@ struct Property<'a> {
 	default_args_update: Cow<'a, str>,
 	/// If an api getter should be created for this property.
 	api_getter: bool,
+	/// If the api getter is written by hand. The generated getter is left
+	/// out, but the property is still part of the property enums.
+	custom_getter: bool,
 	/// If the getter method should be public.
 	public: bool,
 	/// If this property needs to be requested.
@@ -63,6 +66,8 @@ impl<'a> Property<'a> {
 			|| self.type_s.starts_with("Option")
 			|| self.type_s.starts_with("Map<")
 			|| self.type_s.starts_with("Vec<")
+			|| self.type_s.starts_with("RefCell<")
+			|| self.type_s.starts_with("Cell<")
 	}
 
 	fn create_return_type(&self) -> String {
@@ -190,13 +195,13 @@ impl<'a> Property<'a> {
 					};
 					s.push_str(
 						format!(
-							"{}({}{}::{}).map(|d| Duration::seconds(d as i64))",
+							"{}({}{}::{}).map(|d| ::time::duration_from_secs(d as u64))",
 							function, default_args, self.enum_name, value_name
 						)
 						.as_str(),
 					)
 				}
-				"DateTime<Utc>" => {
+				"Timestamp" => {
 					// Try to get an u64
 					let function: &str = if let Some(f) = self.functions.get("u64") {
 						f
@@ -207,7 +212,8 @@ impl<'a> Property<'a> {
 					};
 					s.push_str(
 						format!(
-							"{}({}{}::{}).map(|d| DateTime::from_timestamp(d as i64, 0).unwrap())",
+							"{}({}{}::{}).map(|d| ::time::timestamp_from_secs(d as \
+							 u64).unwrap_or_default())",
 							function, default_args, self.enum_name, value_name
 						)
 						.as_str(),
@@ -238,7 +244,7 @@ impl<'a> serde::Serialize for Property<'a> {
 	fn serialize<S: serde::Serializer>(
 		&self, serializer: S,
 	) -> std::result::Result<S::Ok, S::Error> {
-		let mut s = serializer.serialize_struct("Property", 22)?;
+		let mut s = serializer.serialize_struct("Property", 23)?;
 
 		// Attributes
 		s.serialize_field("name", &self.name)?;
@@ -259,6 +265,7 @@ impl<'a> serde::Serialize for Property<'a> {
 		s.serialize_field("default_args", &self.default_args)?;
 		s.serialize_field("default_args_update", &self.default_args_update)?;
 		s.serialize_field("api_getter", &self.api_getter)?;
+		s.serialize_field("custom_getter", &self.custom_getter)?;
 		s.serialize_field("public", &self.public)?;
 		s.serialize_field("requested", &self.requested)?;
 
@@ -290,6 +297,7 @@ struct PropertyBuilder<'a> {
 	default_args: Cow<'a, str>,
 	default_args_update: Cow<'a, str>,
 	api_getter: bool,
+	custom_getter: bool,
 	public: bool,
 	requested: bool,
 }
@@ -405,6 +413,12 @@ impl<'a> PropertyBuilder<'a> {
 		res
 	}
 
+	fn custom_getter(&self, custom_getter: bool) -> PropertyBuilder<'a> {
+		let mut res = self.clone();
+		res.custom_getter = custom_getter;
+		res
+	}
+
 	fn public(&self, public: bool) -> PropertyBuilder<'a> {
 		let mut res = self.clone();
 		res.public = public;
@@ -435,6 +449,7 @@ impl<'a> PropertyBuilder<'a> {
 			default_args: self.default_args,
 			default_args_update: self.default_args_update,
 			api_getter: self.api_getter,
+			custom_getter: self.custom_getter,
 			public: self.public,
 			requested: self.requested,
 		}
@@ -53,14 +53,22 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 			builder_i32.name("codec_quality").finalize(),
 			builder_i32.name("max_clients").finalize(),
 			builder_i32.name("max_family_clients").finalize(),
-			builder_i32.name("order").finalize(),
+			builder
+				.name("order")
+				.type_s("ChannelId")
+				.update(
+					"ChannelData::get_property_as_uint64(self.server_id, self.id, \
+					 ChannelProperties::Order).map(ChannelId)",
+				)
+				.documentation("The channel above this channel, 0 if it is the first subchannel")
+				.finalize(),
 			builder_bool.name("permanent").value_name("FlagPermanent").finalize(),
 			builder_bool.name("semi_permanent").value_name("FlagSemiPermanent").finalize(),
 			builder_bool.name("default").value_name("FlagDefault").finalize(),
 			builder_bool.name("password").value_name("FlagPassword").finalize(),
 			builder_i32.name("codec_latency_factor").finalize(),
 			builder_bool.name("codec_is_unencrypted").finalize(),
-			builder_i32.name("delete_delay").finalize(),
+			builder.name("delete_delay").type_s("Duration").finalize(),
 			builder_bool
 				.name("max_clients_unlimited")
 				.value_name("FlagMaxClientsUnlimited")
@@ -78,7 +86,11 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 			builder_i32.name("needed_talk_power").finalize(),
 			builder_i32.name("forced_silence").finalize(),
 			builder_string.name("phonetic_name").value_name("NamePhonetic").finalize(),
-			builder_i32.name("icon_id").finalize(),
+			builder
+				.name("icon_id")
+				.type_s("u64")
+				.documentation("The id of the icon file, zero if the channel has no icon")
+				.finalize(),
 			builder_string.name("banner_gfx_url").value_name("BannerGfxUrl").finalize(),
 			builder
 				.name("banner_mode")
@@ -86,7 +98,13 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 				.type_s("HostbannerMode")
 				.finalize(),
 			// Requested
-			builder_string.name("description").requested(true).finalize(),
+			builder_string
+				.name("description")
+				.documentation(
+					"Only available after it was requested explicitly through \
+					 `request_description`.",
+				)
+				.finalize(),
 		])
 		.finalize();
 
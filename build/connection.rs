@@ -62,7 +62,7 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 			 self.get_channel(),\n},
 			ConnectionProperty::Channel {\n\tproperty: \
 			 ConnectionChannelProperty::ChannelGroupInheritedChannel,\n\tdata: \
-			 self.get_channel_group_inherited_channel(),\n},",
+			 self.get_channel_group_inherited_channel().and_then(|c| c.ok_or(Error::Ok)),\n},",
 		)
 		.properties(vec![
 			builder.name("id").type_s("ConnectionId").result(false).api_getter(false).finalize(),
@@ -76,12 +76,45 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 			// ClientProperties
 			client_b_string.name("uid").value_name("UniqueIdentifier").finalize(),
 			client_b_string.name("name").value_name("Nickname").finalize(),
+			client_b_string
+				.name("display_name")
+				.update("Self::query_display_name(self.server_id, self.id)")
+				.documentation(
+					"The name that is shown for this connection, e.g. the nickname of a contact.",
+				)
+				.finalize(),
 			client_b.name("talking").type_s("TalkStatus").value_name("FlagTalking").finalize(),
 			client_b
 				.name("whispering")
 				.type_s("bool")
 				.update("Self::query_whispering(self.server_id, self.id)")
 				.finalize(),
+			client_b
+				.name("talking_since")
+				.type_s("Option<Timestamp>")
+				.result(false)
+				.initialisation("None")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// If the variables were requested with `request_variables` and if
+			// they arrived, TeamSpeak does not tell if they are available
+			client_b
+				.name("variables_requested")
+				.type_s("Cell<bool>")
+				.result(false)
+				.initialisation("Cell::new(false)")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			client_b
+				.name("variables_received")
+				.type_s("bool")
+				.result(false)
+				.initialisation("false")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
 			client_b.name("away").type_s("AwayStatus").finalize(),
 			client_b_string.name("away_message").finalize(),
 			client_b.name("input_muted").type_s("MuteInputStatus").finalize(),
@@ -99,8 +132,8 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 			client_b.name("channel_group_id").type_s("ChannelGroupId").finalize(),
 			client_b.name("server_groups").type_s("Vec<ServerGroupId>").finalize(),
 			client_b.name("talk_power").type_s("i32").finalize(),
-			// When this client requested to talk
-			client_b.name("talk_request").type_s("DateTime<Utc>").finalize(),
+			// When this client requested to talk, zero if there is no request
+			client_b.name("talk_request").type_s("Timestamp").finalize(),
 			client_b
 				.name("talk_request_message")
 				.type_s("String")
@@ -143,27 +176,41 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 				.documentation("Only available for server queries")
 				.finalize(),
 			// Requested
-			client_b_string_r.name("version").finalize(),
-			client_b_string_r.name("platform").finalize(),
-			client_b_r.name("created").type_s("DateTime<Utc>").finalize(),
+			client_b_string_r.name("version").custom_getter(true).finalize(),
+			client_b_string_r.name("platform").custom_getter(true).finalize(),
+			client_b_r.name("created").type_s("Timestamp").finalize(),
 			client_b_r
 				.name("last_connected")
-				.type_s("DateTime<Utc>")
+				.type_s("Timestamp")
 				.value_name("Lastconnected")
 				.finalize(),
-			client_b_i32_r.name("total_connections").value_name("Totalconnections").finalize(),
-			builder_r.name("ping").type_s("Duration").finalize(),
+			client_b_i32_r
+				.name("total_connections")
+				.value_name("Totalconnections")
+				.custom_getter(true)
+				.finalize(),
+			// The ping is in milliseconds
+			builder_r
+				.name("ping")
+				.type_s("Duration")
+				.update(
+					"ConnectionData::get_connection_property_as_uint64(self.server_id, self.id, \
+					 ConnectionProperties::Ping).map(::time::duration_from_millis)",
+				)
+				.custom_getter(true)
+				.finalize(),
 			builder_r.name("ping_deviation").type_s("Duration").finalize(),
-			builder_r.name("connected_time").type_s("Duration").finalize(),
-			builder_r.name("idle_time").type_s("Duration").finalize(),
-			builder_string_r.name("client_ip").finalize(),
+			builder_r.name("connected_time").type_s("Duration").custom_getter(true).finalize(),
+			builder_r.name("idle_time").type_s("Duration").custom_getter(true).finalize(),
+			builder_string_r.name("client_ip").custom_getter(true).finalize(),
 			builder_r
 				.name("client_port")
 				.type_s("u16")
 				.update(
-					"ConnectionData::get_connection_property_as_uint64(self.server_id, self.id, \
-					 ConnectionProperties::ClientPort).map(|p| p as u16)",
+					"ConnectionData::get_connection_property_as_port(self.server_id, self.id, \
+					 ConnectionProperties::ClientPort)",
 				)
+				.custom_getter(true)
 				.finalize(),
 			// Network
 			builder_u64_r.name("packets_sent_speech").finalize(),
@@ -260,11 +307,12 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 			client_b_i32_r.name("needed_serverquery_view_power").finalize(),
 			client_b_i32_r.name("icon_id").finalize(),
 			client_b_r.name("is_channel_commander").type_s("bool").finalize(),
-			client_b_string_r.name("country").finalize(),
-			client_b_string_r.name("badges").finalize(),
-			client_b_string_r.name("myteamspeak_id").finalize(),
-			client_b_string_r.name("integrations").finalize(),
-			client_b_string_r.name("active_integrations_info").finalize(),
+			// The api getters request these properties automatically
+			client_b_string_r.name("country").custom_getter(true).finalize(),
+			client_b_string_r.name("badges").custom_getter(true).finalize(),
+			client_b_string_r.name("myteamspeak_id").custom_getter(true).finalize(),
+			client_b_string_r.name("integrations").custom_getter(true).finalize(),
+			client_b_string_r.name("active_integrations_info").custom_getter(true).finalize(),
 		])
 		.finalize();
 
@@ -58,13 +58,34 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 				.finalize(),
 			builder_string.name("name").finalize(),
 			builder_string.name("phonetic_name").value_name("NamePhonetic").finalize(),
-			builder_string.name("platform").finalize(),
-			builder_string.name("version").finalize(),
+			// The platform and version do not change while we are connected
+			builder_string
+				.name("platform")
+				.update(
+					"Self::query_string_once(&self.platform, self.id, \
+					 VirtualServerProperties::Platform)",
+				)
+				.finalize(),
+			builder_string
+				.name("version")
+				.update(
+					"Self::query_string_once(&self.version, self.id, \
+					 VirtualServerProperties::Version)",
+				)
+				.finalize(),
 			builder_string.name("nickname").finalize(),
 			builder_string.name("accounting_token").finalize(),
-			// TODO Always zero when queried as string, int or uint64
-			builder.name("created").type_s("DateTime<Utc>").finalize(),
-			builder.name("codec_encryption_mode").type_s("CodecEncryptionMode").finalize(),
+			// Zero if not requested, the api getter falls back to the uptime
+			builder_r.name("created").type_s("Timestamp").custom_getter(true).finalize(),
+			builder
+				.name("codec_encryption_mode")
+				.type_s("CodecEncryptionMode")
+				.update(
+					"ServerData::get_property_as_int(self.id, \
+					 VirtualServerProperties::CodecEncryptionMode).\
+					 and_then(Self::codec_encryption_mode_from_int)",
+				)
+				.finalize(),
 			// TODO Update
 			builder.name("default_server_group").type_s("ServerGroupId").finalize(),
 			builder.name("default_channel_group").type_s("ChannelGroupId").finalize(),
@@ -96,6 +117,103 @@ pub(crate) fn create() -> Vec<Struct<'static>> {
 				.update("Self::query_channels(self.id)")
 				.api_getter(false)
 				.finalize(),
+			builder
+				.name("subchannels")
+				.type_s("Map<ChannelId, Vec<ChannelId>>")
+				.result(false)
+				.initialisation("Map::new()")
+				.update("Self::index_subchannels(&self.channels)")
+				.api_getter(false)
+				.finalize(),
+			builder
+				.name("server_groups")
+				.type_s("Map<ServerGroupId, ServerGroup>")
+				.result(false)
+				.initialisation("Map::new()")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// The server group list that is currently received
+			builder
+				.name("new_server_groups")
+				.type_s("Map<ServerGroupId, ServerGroup>")
+				.result(false)
+				.initialisation("Map::new()")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// If the server group list was requested and did not arrive yet
+			builder
+				.name("server_group_list_requested")
+				.type_s("Cell<bool>")
+				.result(false)
+				.initialisation("Cell::new(false)")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			builder
+				.name("channel_groups")
+				.type_s("Map<ChannelGroupId, ChannelGroup>")
+				.result(false)
+				.initialisation("Map::new()")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// The channel group list that is currently received
+			builder
+				.name("new_channel_groups")
+				.type_s("Map<ChannelGroupId, ChannelGroup>")
+				.result(false)
+				.initialisation("Map::new()")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// If the channel group list was requested and did not arrive yet
+			builder
+				.name("channel_group_list_requested")
+				.type_s("Cell<bool>")
+				.result(false)
+				.initialisation("Cell::new(false)")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			builder
+				.name("channel_client_permissions")
+				.type_s("Map<(ChannelId, u64), Vec<ChannelClientPermission>>")
+				.result(false)
+				.initialisation("Map::new()")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// The channel client permissions that are currently received
+			builder
+				.name("new_channel_client_permissions")
+				.type_s("Map<(ChannelId, u64), Vec<ChannelClientPermission>>")
+				.result(false)
+				.initialisation("Map::new()")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// The file list entries that are currently received, by channel and
+			// path
+			builder
+				.name("file_lists")
+				.type_s("RefCell<Map<(ChannelId, String), Vec<FileEntry>>>")
+				.result(false)
+				.initialisation("RefCell::new(Map::new())")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
+			// The server groups of clients that were received since they were
+			// requested, by database id
+			builder
+				.name("client_server_groups")
+				.type_s("RefCell<Map<u64, Vec<ServerGroup>>>")
+				.result(false)
+				.initialisation("RefCell::new(Map::new())")
+				.should_update(false)
+				.api_getter(false)
+				.finalize(),
 			// TODO requested
 			builder_string_r.name("welcome_message").value_name("Welcomemessage").finalize(),
 			builder_i32_r.name("max_clients").finalize(),
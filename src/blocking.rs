@@ -0,0 +1,80 @@
+//! Block the current thread until TeamSpeak answered a request.
+//!
+//! TeamSpeak delivers the answers on its main thread. Waiting in a callback
+//! of the plugin blocks that thread, so the answer cannot arrive and the
+//! wait ends only with the timeout. Use these functions only from an own
+//! thread.
+//!
+//! This module is only available with the `blocking` feature.
+
+use std::collections::HashMap as Map;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use {ConnectionId, Error, ServerId};
+
+/// The number of waiting threads and received updates for a connection.
+#[derive(Default)]
+struct Waiting {
+	waiters: usize,
+	updates: u64,
+}
+
+type WaitingMap = Map<(ServerId, ConnectionId), Waiting>;
+
+lazy_static! {
+	/// Connections for which threads wait for an update.
+	static ref WAITING: (Mutex<WaitingMap>, Condvar) =
+		(Mutex::new(Map::new()), Condvar::new());
+}
+
+/// Call `request` and wait until the variables of a connection were updated.
+///
+/// Fails with `Error::CurrentlyNotPossible` if no update arrives within
+/// `timeout`.
+pub(crate) fn wait_for_update<F: FnOnce() -> Result<(), Error>>(
+	server_id: ServerId, connection_id: ConnectionId, timeout: Duration, request: F,
+) -> Result<(), Error> {
+	let key = (server_id, connection_id);
+	let (ref lock, ref condvar) = *WAITING;
+	let start = {
+		let mut waiting = lock.lock().unwrap();
+		let entry = waiting.entry(key).or_default();
+		entry.waiters += 1;
+		entry.updates
+	};
+
+	let mut res = request();
+	if res.is_ok() {
+		let deadline = Instant::now() + timeout;
+		let mut waiting = lock.lock().unwrap();
+		while waiting[&key].updates == start {
+			let now = Instant::now();
+			if now >= deadline {
+				res = Err(Error::CurrentlyNotPossible);
+				break;
+			}
+			waiting = condvar.wait_timeout(waiting, deadline - now).unwrap().0;
+		}
+	}
+
+	let mut waiting = lock.lock().unwrap();
+	let remove = {
+		let entry = waiting.get_mut(&key).unwrap();
+		entry.waiters -= 1;
+		entry.waiters == 0
+	};
+	if remove {
+		waiting.remove(&key);
+	}
+	res
+}
+
+/// Wake up the threads that wait for an update of this connection.
+pub(crate) fn connection_updated(server_id: ServerId, connection_id: ConnectionId) {
+	let (ref lock, ref condvar) = *WAITING;
+	if let Some(entry) = lock.lock().unwrap().get_mut(&(server_id, connection_id)) {
+		entry.updates += 1;
+		condvar.notify_all();
+	}
+}
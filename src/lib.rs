@@ -52,7 +52,7 @@
 //!
 //!     // Implement callbacks here
 //!
-//!     fn shutdown(&mut self, api: &TsApi) {
+//!     fn shutdown(&mut self, api: &TsApi, reason: ShutdownReason) {
 //!         api.log_or_print("Shutdown", "MyTsPlugin", LogLevel::Info);
 //!     }
 //! }
@@ -65,6 +65,7 @@
 // TODO This should be removed at some time, when more code is ready
 #![allow(dead_code)]
 
+#[cfg(feature = "chrono")]
 extern crate chrono;
 #[macro_use]
 extern crate lazy_static;
@@ -76,15 +77,22 @@ pub use ts3plugin_sys::public_errors::Error;
 pub use ts3plugin_sys::ts3functions::Ts3Functions;
 
 pub use plugin::*;
+pub use time::{Duration, Timestamp};
 
-use chrono::*;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap as Map;
+use std::collections::{HashSet, VecDeque};
+use std::convert::TryFrom;
 use std::ffi::{CStr, CString};
 use std::fmt;
+use std::fs;
+use std::io;
 use std::mem::transmute;
 use std::ops::{Deref, DerefMut};
-use std::os::raw::{c_char, c_int};
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::path::{Path, PathBuf};
 use std::sync::MutexGuard;
+use std::sync::mpsc::{self, Receiver};
 
 /// Converts a normal `String` to a `CString`.
 macro_rules! to_cstring {
@@ -101,7 +109,14 @@ macro_rules! to_string {
 }
 
 // Declare modules here so the macros are visible in the modules
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod plugin;
+#[cfg(feature = "async")]
+pub mod request;
+#[cfg(test)]
+mod tests;
+mod time;
 pub mod ts3interface;
 
 // Import automatically generated structs
@@ -119,18 +134,113 @@ pub static mut TS3_FUNCTIONS: Option<Ts3Functions> = None;
 // ******************** Structs ********************
 /// The possible receivers of a message. A message can be sent to a specific
 /// connection, to the current channel chat or to the server chat.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MessageReceiver {
 	Connection(ConnectionId),
 	Channel,
 	Server,
 }
 
+impl MessageReceiver {
+	/// Answer to a message that was received with this target.
+	///
+	/// Private messages are answered privately to the `invoker` who sent the
+	/// message, channel messages in the channel of our own client and server
+	/// messages in the server chat.
+	pub fn reply<S: AsRef<str>>(
+		&self, server: &Server, invoker: &Invoker, message: S,
+	) -> Result<(), Error> {
+		match *self {
+			MessageReceiver::Connection(_) => server
+				.get_connection(invoker.get_id())
+				.ok_or(Error::ClientInvalidId)?
+				.send_message(message),
+			MessageReceiver::Channel => {
+				server.get_own_connection()?.get_channel()?.send_message(message)
+			}
+			MessageReceiver::Server => server.send_message(message),
+		}
+	}
+}
+
+/// An event that was recorded in the event log.
+///
+/// See [`TsApi::enable_event_log`].
+///
+/// [`TsApi::enable_event_log`]: struct.TsApi.html#method.enable_event_log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+	/// A connection connected to the server.
+	Joined { server_id: ServerId, connection_id: ConnectionId, name: String },
+	/// A connection disconnected from the server.
+	Left { server_id: ServerId, connection_id: ConnectionId, name: String, message: String },
+	/// A connection switched the channel.
+	Moved {
+		server_id: ServerId,
+		connection_id: ConnectionId,
+		name: String,
+		old_channel_id: ChannelId,
+		new_channel_id: ChannelId,
+	},
+	/// A text message was received.
+	Message {
+		server_id: ServerId,
+		invoker_id: ConnectionId,
+		invoker_name: String,
+		receiver: MessageReceiver,
+		message: String,
+	},
+}
+
+/// The possible receivers of a plugin message.
+///
+/// Plugin messages are only received by clients that have a plugin with the
+/// same plugin id installed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PluginTarget {
+	/// All connections on the server.
+	Server,
+	/// All connections in the current channel of our own client.
+	CurrentChannel,
+	/// All connections in the current channel of our own client that are
+	/// subscribed to this channel.
+	CurrentChannelSubscribed,
+	/// A list of connections.
+	Clients(Vec<ConnectionId>),
+}
+
+/// Helper methods for `ConnectStatus`.
+pub trait ConnectStatusExt {
+	/// If the server accepted the connection and we got a connection id.
+	/// The channels and connections may not be available yet, that is the
+	/// case when the status reached `ConnectionEstablished`.
+	fn is_connected(&self) -> bool;
+	/// If we are trying to connect but the server did not accept us yet.
+	fn is_connecting(&self) -> bool;
+}
+
+impl ConnectStatusExt for ConnectStatus {
+	fn is_connected(&self) -> bool {
+		match *self {
+			ConnectStatus::Connected
+			| ConnectStatus::ConnectionEstablishing
+			| ConnectStatus::ConnectionEstablished => true,
+			ConnectStatus::Disconnected | ConnectStatus::Connecting => false,
+		}
+	}
+
+	fn is_connecting(&self) -> bool { *self == ConnectStatus::Connecting }
+}
+
 /// Permissions - TODO not yet implemented
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Permissions;
 
 /// A wrapper for a server id.
+///
+/// The id belongs to a server connection handler (a tab in the client), so
+/// it is only stable as long as the connection exists. To recognize a server
+/// after a reconnect, use its unique identifier (`Server::get_uid`).
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub struct ServerId(u64);
 
@@ -149,21 +259,95 @@ pub struct Permission {}
 pub struct PermissionId(u32);
 
 #[derive(Debug, Clone)]
-pub struct ServerGroup {}
+pub struct ServerGroup {
+	id: ServerGroupId,
+	name: String,
+	icon_id: i32,
+}
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub struct ServerGroupId(u64);
 
 #[derive(Debug, Clone)]
-pub struct ChannelGroup {}
+pub struct ChannelGroup {
+	id: ChannelGroupId,
+	name: String,
+	icon_id: i32,
+}
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub struct ChannelGroupId(u64);
 
+/// A file or directory in the file list of a channel.
+///
+/// See [`Server::request_file_list`].
+///
+/// [`Server::request_file_list`]: struct.Server.html#method.request_file_list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+	name: String,
+	size: u64,
+	datetime: Timestamp,
+	file_type: FileListType,
+	incomplete_size: u64,
+}
+
+/// A permission that is set for a client in a specific channel.
+///
+/// See [`Server::get_channel_client_permissions`].
+///
+/// [`Server::get_channel_client_permissions`]: struct.Server.html#method.get_channel_client_permissions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelClientPermission {
+	id: PermissionId,
+	value: i32,
+	negated: bool,
+	skip: bool,
+}
+
 // ******************** Implementation ********************
 
+// ********** Ids **********
+impl ServerId {
+	/// Create a server id from its raw value, e.g. to restore a saved id.
+	pub fn new(id: u64) -> ServerId { ServerId(id) }
+
+	/// Get the raw value of this id as it is used by TeamSpeak.
+	pub fn as_raw(&self) -> u64 { self.0 }
+}
+
+impl ChannelId {
+	/// Create a channel id from its raw value, e.g. to restore a saved id.
+	pub fn new(id: u64) -> ChannelId { ChannelId(id) }
+
+	/// Get the raw value of this id as it is used by TeamSpeak.
+	pub fn as_raw(&self) -> u64 { self.0 }
+}
+
+impl ConnectionId {
+	/// Create a connection id from its raw value, e.g. to restore a saved id.
+	pub fn new(id: u16) -> ConnectionId { ConnectionId(id) }
+
+	/// Get the raw value of this id as it is used by TeamSpeak.
+	pub fn as_raw(&self) -> u16 { self.0 }
+}
+
+impl PermissionId {
+	/// Create a permission id from its raw value.
+	pub fn new(id: u32) -> PermissionId { PermissionId(id) }
+
+	/// Get the raw value of this id as it is used by TeamSpeak.
+	pub fn as_raw(&self) -> u32 { self.0 }
+}
+
 // ********** Invoker **********
-#[derive(Debug, Eq)]
+/// The identity of an invoker without a reference to the server.
+///
+/// It can be cloned and kept after the callback which received the
+/// [`Invoker`] returned.
+///
+/// [`Invoker`]: struct.Invoker.html
+#[derive(Debug, Clone, Eq)]
 pub struct InvokerData {
 	id: ConnectionId,
 	uid: String,
@@ -191,7 +375,7 @@ impl InvokerData {
 
 /// The invoker is maybe not visible to the user, but we can get events caused
 /// by him, so some information about him are passed along with his id.
-#[derive(Debug, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub struct Invoker<'a> {
 	server: Server<'a>,
 	data: InvokerData,
@@ -208,7 +392,312 @@ impl<'a> Deref for Invoker<'a> {
 impl<'a> Invoker<'a> {
 	fn new(server: Server<'a>, data: InvokerData) -> Invoker<'a> { Invoker { server, data } }
 
+	/// Get the server where the invoker caused the event.
+	pub fn get_server(&self) -> Server<'a> { self.server.clone() }
+
 	pub fn get_connection(&self) -> Option<Connection> { self.server.get_connection(self.id) }
+
+	/// Get the identity of this invoker, clone it to keep it after the
+	/// callback returned.
+	pub fn get_data(&self) -> &InvokerData { &self.data }
+}
+
+// ********** AudioPacket **********
+/// Voice data that was pushed into the audio buffer.
+///
+/// See [`TsApi::enable_audio_buffer`].
+///
+/// [`TsApi::enable_audio_buffer`]: struct.TsApi.html#method.enable_audio_buffer
+#[derive(Debug, Clone)]
+pub struct AudioPacket {
+	server_id: ServerId,
+	connection_id: Option<ConnectionId>,
+	samples: Vec<i16>,
+	channels: i32,
+}
+
+impl AudioPacket {
+	fn new(
+		server_id: ServerId, connection_id: Option<ConnectionId>, samples: Vec<i16>, channels: i32,
+	) -> AudioPacket {
+		AudioPacket { server_id, connection_id, samples, channels }
+	}
+
+	/// Get the id of the server where the audio was played or recorded.
+	pub fn get_server_id(&self) -> ServerId { self.server_id }
+
+	/// Get the id of the connection that sent the audio, `None` for audio that
+	/// was recorded from our own capture device.
+	pub fn get_connection_id(&self) -> Option<ConnectionId> { self.connection_id }
+
+	/// Get the samples, they are 16 bit with 48 kHz and packed (interleaved).
+	pub fn get_samples(&self) -> &[i16] { &self.samples }
+
+	/// Get the number of channels.
+	pub fn get_channels(&self) -> i32 { self.channels }
+}
+
+// ********** AudioFrame **********
+/// A view on interleaved voice data, like it is passed to the voice data
+/// callbacks, e.g. [`Plugin::captured_voice_data`].
+///
+/// ```
+/// # use ts3plugin::AudioFrame;
+/// let mut samples = [1, 2, 3, 4, 5, 6];
+/// let mut frame = AudioFrame::new(&mut samples, 2);
+/// assert_eq!(frame.deinterleave(), vec![vec![1, 3, 5], vec![2, 4, 6]]);
+///
+/// for s in frame.per_channel_iter_mut(1) {
+///     *s = 0;
+/// }
+/// assert_eq!(frame.get_samples(), &[1, 0, 3, 0, 5, 0]);
+///
+/// frame.interleave(&[vec![7, 8, 9], vec![10, 11, 12]]);
+/// assert_eq!(frame.get_samples(), &[7, 10, 8, 11, 9, 12]);
+/// ```
+///
+/// [`Plugin::captured_voice_data`]: plugin/trait.Plugin.html#method.captured_voice_data
+#[derive(Debug)]
+pub struct AudioFrame<'a> {
+	samples: &'a mut [i16],
+	channels: usize,
+}
+
+impl<'a> AudioFrame<'a> {
+	/// The sample rate of all voice data in TeamSpeak.
+	pub const SAMPLE_RATE: u32 = 48000;
+
+	/// Create a view on interleaved samples with the given number of channels.
+	///
+	/// # Panics
+	///
+	/// If `channels` is zero.
+	pub fn new(samples: &'a mut [i16], channels: usize) -> AudioFrame<'a> {
+		assert!(channels > 0, "An audio frame needs at least one channel");
+		AudioFrame { samples, channels }
+	}
+
+	/// Get the number of channels.
+	pub fn get_channels(&self) -> usize { self.channels }
+
+	/// Get the sample rate, this is always [`SAMPLE_RATE`].
+	///
+	/// [`SAMPLE_RATE`]: #associatedconstant.SAMPLE_RATE
+	pub fn get_sample_rate(&self) -> u32 { Self::SAMPLE_RATE }
+
+	/// Get the number of samples per channel.
+	pub fn get_frame_count(&self) -> usize { self.samples.len() / self.channels }
+
+	/// Get the interleaved samples.
+	pub fn get_samples(&self) -> &[i16] { self.samples }
+
+	/// Get the interleaved samples mutably.
+	pub fn get_samples_mut(&mut self) -> &mut [i16] { self.samples }
+
+	/// Iterate over the samples of a single channel.
+	///
+	/// The iterator is empty if the channel does not exist.
+	pub fn per_channel_iter_mut(&mut self, channel: usize) -> impl Iterator<Item = &mut i16> {
+		let count = if channel < self.channels { self.get_frame_count() } else { 0 };
+		self.samples.iter_mut().skip(channel).step_by(self.channels).take(count)
+	}
+
+	/// Copy the samples into one list per channel.
+	pub fn deinterleave(&self) -> Vec<Vec<i16>> {
+		let frames = self.get_frame_count();
+		(0..self.channels)
+			.map(|c| (0..frames).map(|f| self.samples[f * self.channels + c]).collect())
+			.collect()
+	}
+
+	/// Write one list of samples per channel back into the interleaved samples.
+	///
+	/// Channels and samples that do not fit into this frame are ignored,
+	/// samples that are missing in `channels` stay unchanged.
+	pub fn interleave<C: AsRef<[i16]>>(&mut self, channels: &[C]) {
+		let frames = self.get_frame_count();
+		for (c, channel) in channels.iter().enumerate().take(self.channels) {
+			for (f, sample) in channel.as_ref().iter().enumerate().take(frames) {
+				self.samples[f * self.channels + c] = *sample;
+			}
+		}
+	}
+}
+
+// ********** ServerGroup **********
+impl ServerGroup {
+	fn new(id: ServerGroupId, name: String, icon_id: i32) -> ServerGroup {
+		ServerGroup { id, name, icon_id }
+	}
+
+	/// Get the id of this server group.
+	pub fn get_id(&self) -> ServerGroupId { self.id }
+
+	/// Get the name of this server group, it is empty if the group is unknown.
+	pub fn get_name(&self) -> &str { &self.name }
+
+	/// Get the id of the icon of this server group, zero if it has no icon.
+	pub fn get_icon_id(&self) -> i32 { self.icon_id }
+
+	/// Download the icon of this server group into the directory `dest`.
+	///
+	/// See [`Server::download_icon`].
+	///
+	/// [`Server::download_icon`]: struct.Server.html#method.download_icon
+	pub fn download_icon(&self, server: &Server, dest: &Path) -> Result<PathBuf, Error> {
+		// TeamSpeak reports icon ids as signed integers
+		server.download_icon(u64::from(self.icon_id as u32), dest)
+	}
+}
+
+// ********** ChannelGroup **********
+impl ChannelGroup {
+	fn new(id: ChannelGroupId, name: String, icon_id: i32) -> ChannelGroup {
+		ChannelGroup { id, name, icon_id }
+	}
+
+	/// Get the id of this channel group.
+	pub fn get_id(&self) -> ChannelGroupId { self.id }
+
+	/// Get the name of this channel group, it is empty if the group is unknown.
+	pub fn get_name(&self) -> &str { &self.name }
+
+	/// Get the id of the icon of this channel group, zero if it has no icon.
+	pub fn get_icon_id(&self) -> i32 { self.icon_id }
+
+	/// Download the icon of this channel group into the directory `dest`.
+	///
+	/// See [`Server::download_icon`].
+	///
+	/// [`Server::download_icon`]: struct.Server.html#method.download_icon
+	pub fn download_icon(&self, server: &Server, dest: &Path) -> Result<PathBuf, Error> {
+		// TeamSpeak reports icon ids as signed integers
+		server.download_icon(u64::from(self.icon_id as u32), dest)
+	}
+}
+
+// ********** FileEntry **********
+impl FileEntry {
+	/// Get the name of this file or directory.
+	pub fn get_name(&self) -> &str { &self.name }
+
+	/// Get the size of this file in bytes.
+	pub fn get_size(&self) -> u64 { self.size }
+
+	/// Get the time when this file was changed the last time.
+	pub fn get_datetime(&self) -> Timestamp { self.datetime }
+
+	/// If this entry is a file or a directory.
+	pub fn get_file_type(&self) -> FileListType { self.file_type }
+
+	/// Get the number of bytes that are uploaded, if the upload of this file
+	/// did not finish yet.
+	pub fn get_incomplete_size(&self) -> u64 { self.incomplete_size }
+}
+
+// ********** ChannelClientPermission **********
+impl ChannelClientPermission {
+	fn new(id: PermissionId, value: i32, negated: bool, skip: bool) -> ChannelClientPermission {
+		ChannelClientPermission { id, value, negated, skip }
+	}
+
+	/// Get the id of this permission.
+	pub fn get_id(&self) -> PermissionId { self.id }
+
+	/// Get the value that is set for this permission.
+	pub fn get_value(&self) -> i32 { self.value }
+
+	/// If the permission is negated.
+	pub fn is_negated(&self) -> bool { self.negated }
+
+	/// If the permission skips permissions of higher levels.
+	pub fn is_skip(&self) -> bool { self.skip }
+}
+
+// ********** ServerRefresh **********
+/// What changed when the cached data of a server was refreshed.
+///
+/// See [`TsApi::refresh_server`].
+///
+/// [`TsApi::refresh_server`]: struct.TsApi.html#method.refresh_server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerRefresh {
+	added_connections: usize,
+	removed_connections: usize,
+	added_channels: usize,
+	removed_channels: usize,
+}
+
+impl ServerRefresh {
+	/// Get the number of connections that were not cached before.
+	pub fn get_added_connections(&self) -> usize { self.added_connections }
+
+	/// Get the number of cached connections that do not exist anymore.
+	pub fn get_removed_connections(&self) -> usize { self.removed_connections }
+
+	/// Get the number of channels that were not cached before.
+	pub fn get_added_channels(&self) -> usize { self.added_channels }
+
+	/// Get the number of cached channels that do not exist anymore.
+	pub fn get_removed_channels(&self) -> usize { self.removed_channels }
+}
+
+// ********** AntifloodSettings **********
+/// The antiflood settings of a server.
+///
+/// See [`Server::get_antiflood`].
+///
+/// [`Server::get_antiflood`]: struct.Server.html#method.get_antiflood
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AntifloodSettings {
+	points_tick_reduce: i32,
+	points_needed_command_block: i32,
+	points_needed_ip_block: i32,
+	points_needed_plugin_block: i32,
+}
+
+impl AntifloodSettings {
+	/// Get the number of antiflood points that are removed from a client
+	/// every tick.
+	pub fn get_points_tick_reduce(&self) -> i32 { self.points_tick_reduce }
+
+	/// Get the number of antiflood points after which commands of a client
+	/// are blocked.
+	pub fn get_points_needed_command_block(&self) -> i32 { self.points_needed_command_block }
+
+	/// Get the number of antiflood points after which the ip of a client is
+	/// banned.
+	pub fn get_points_needed_ip_block(&self) -> i32 { self.points_needed_ip_block }
+
+	/// Get the number of antiflood points after which plugin commands of a
+	/// client are blocked.
+	pub fn get_points_needed_plugin_block(&self) -> i32 { self.points_needed_plugin_block }
+}
+
+// ********** ServerVersionInfo **********
+/// The version information of a server.
+///
+/// See [`Server::get_version_info`].
+///
+/// [`Server::get_version_info`]: struct.Server.html#method.get_version_info
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerVersionInfo {
+	version: String,
+	platform: String,
+	min_client_version: Option<String>,
+}
+
+impl ServerVersionInfo {
+	/// Get the version of the server.
+	pub fn get_version(&self) -> &str { &self.version }
+
+	/// Get the platform that the server runs on.
+	pub fn get_platform(&self) -> &str { &self.platform }
+
+	/// Get the lowest client version that is allowed to connect.
+	///
+	/// `None` if it was not requested yet.
+	pub fn get_min_client_version(&self) -> Option<&str> { self.min_client_version.as_deref() }
 }
 
 // ********** Server **********
@@ -362,6 +851,47 @@ impl ServerData {
 		}
 	}
 
+	/// Keep the value of a string property once it is known, otherwise ask
+	/// TeamSpeak for it.
+	/// Used for properties that do not change during a connection.
+	fn query_string_once(
+		current: &Result<String, Error>, id: ServerId, property: VirtualServerProperties,
+	) -> Result<String, Error> {
+		match *current {
+			Ok(ref value) => Ok(value.clone()),
+			Err(_) => Self::get_property_as_string(id, property),
+		}
+	}
+
+	/// Convert the codec encryption mode from TeamSpeak.
+	///
+	/// Unknown values are reported as `Error::ParameterInvalid` instead of
+	/// being transmuted into an invalid enum value.
+	fn codec_encryption_mode_from_int(mode: i32) -> Result<CodecEncryptionMode, Error> {
+		match mode {
+			0 => Ok(CodecEncryptionMode::PerChannel),
+			1 => Ok(CodecEncryptionMode::ForcedOff),
+			2 => Ok(CodecEncryptionMode::ForcedOn),
+			_ => Err(Error::ParameterInvalid),
+		}
+	}
+
+	/// Map the ids of all channels to the ids of their direct subchannels.
+	/// Called when the channels of a server are updated.
+	fn index_subchannels(
+		channels: &Result<Map<ChannelId, ChannelData>, Error>,
+	) -> Map<ChannelId, Vec<ChannelId>> {
+		let mut index: Map<ChannelId, Vec<ChannelId>> = Map::new();
+		if let Ok(ref cs) = *channels {
+			for c in cs.values() {
+				if let Ok(parent) = c.get_parent_channel_id() {
+					index.entry(parent).or_default().push(c.get_id());
+				}
+			}
+		}
+		index
+	}
+
 	// ********** Private Interface **********
 
 	fn add_connection(&mut self, connection_id: ConnectionId) -> &mut ConnectionData {
@@ -375,11 +905,29 @@ impl ServerData {
 		self.visible_connections.remove(&connection_id)
 	}
 
+	/// Set the channel of a cached connection after it switched the channel.
+	///
+	/// Connections that are not cached are ignored.
+	fn move_connection(&mut self, connection_id: ConnectionId, channel_id: ChannelId) {
+		if let Some(connection) = self.get_mut_connection(connection_id) {
+			connection.channel_id = Ok(channel_id);
+		}
+	}
+
 	fn add_channel(&mut self, channel_id: ChannelId) -> Result<&mut ChannelData, Error> {
+		let mut channel = ChannelData::new(self.id, channel_id);
+		channel.update();
+		self.insert_channel(channel)
+	}
+
+	/// Cache a channel and add it to the subchannels of its parent.
+	fn insert_channel(&mut self, channel: ChannelData) -> Result<&mut ChannelData, Error> {
 		match self.channels {
 			Ok(ref mut cs) => {
-				let mut channel = ChannelData::new(self.id, channel_id);
-				channel.update();
+				let channel_id = channel.get_id();
+				if let Ok(parent) = channel.get_parent_channel_id() {
+					Self::insert_subchannel(&mut self.subchannels, parent, channel_id);
+				}
 				cs.insert(channel_id, channel);
 				Ok(cs.get_mut(&channel_id).unwrap())
 			}
@@ -387,8 +935,54 @@ impl ServerData {
 		}
 	}
 
+	/// Remove a channel from the cache and from the subchannels of its parent.
+	///
+	/// The subchannels of the removed channel are kept, so a channel can be
+	/// updated by removing and adding it again.
 	fn remove_channel(&mut self, channel_id: ChannelId) -> Option<ChannelData> {
-		self.channels.as_mut().ok().and_then(|cs| cs.remove(&channel_id))
+		let channel = self.channels.as_mut().ok().and_then(|cs| cs.remove(&channel_id));
+		if let Some(Ok(parent)) = channel.as_ref().map(|c| c.get_parent_channel_id()) {
+			self.remove_subchannel(parent, channel_id);
+		}
+		channel
+	}
+
+	/// Remove a channel that was deleted on the server, including its entry
+	/// in the subchannel index.
+	fn delete_channel(&mut self, channel_id: ChannelId) -> Option<ChannelData> {
+		self.subchannels.remove(&channel_id);
+		self.remove_channel(channel_id)
+	}
+
+	/// Change the parent of a channel and update the subchannel index.
+	fn set_channel_parent(&mut self, channel_id: ChannelId, parent_channel_id: ChannelId) {
+		let old_parent = match self.get_mut_channel(channel_id) {
+			Some(channel) => {
+				std::mem::replace(&mut channel.parent_channel_id, Ok(parent_channel_id))
+			}
+			None => return,
+		};
+		if let Ok(old_parent) = old_parent {
+			self.remove_subchannel(old_parent, channel_id);
+		}
+		Self::insert_subchannel(&mut self.subchannels, parent_channel_id, channel_id);
+	}
+
+	/// Add a channel to the index of subchannels, if it is not there yet.
+	fn insert_subchannel(
+		subchannels: &mut Map<ChannelId, Vec<ChannelId>>, parent_channel_id: ChannelId,
+		channel_id: ChannelId,
+	) {
+		let siblings = subchannels.entry(parent_channel_id).or_default();
+		if !siblings.contains(&channel_id) {
+			siblings.push(channel_id);
+		}
+	}
+
+	fn remove_subchannel(&mut self, parent_channel_id: ChannelId, channel_id: ChannelId) {
+		if let Some(subchannels) = self.subchannels.get_mut(&parent_channel_id) {
+			subchannels.retain(|c| *c != channel_id);
+		}
 	}
 
 	/// Get the mutable connection on this server that has the specified id, returns
@@ -449,7 +1043,7 @@ impl<'a> Server<'a> {
 			/*self.api.log_or_print(
 			format!("Can't find server group {:?}", server_group_id),
 			"rust-ts3plugin", ::LogLevel::Warning);*/
-			ServerGroup {}
+			ServerGroup::new(server_group_id, String::new(), 0)
 		})
 	}
 
@@ -457,7 +1051,7 @@ impl<'a> Server<'a> {
 		self.get_channel_group(channel_group_id).unwrap_or_else(|| {
 			//self.api.log_or_print(format!("Can't find channel group {:?}", channel_group_id),
 			// "rust-ts3plugin", ::LogLevel::Warning);
-			ChannelGroup {}
+			ChannelGroup::new(channel_group_id, String::new(), 0)
 		})
 	}
 
@@ -469,10 +1063,102 @@ impl<'a> Server<'a> {
 	}*/
 
 	/// Get the own connection to the server.
+	///
+	/// Fails with `Error::NotConnected` while the connection to the server is
+	/// not established yet.
 	pub fn get_own_connection(&self) -> Result<Connection<'a>, Error> {
 		match self.data {
-			Ok(data) => data.get_own_connection_id().map(|id| self.get_connection_unwrap(id)),
-			Err(_) => Err(Error::Ok),
+			Ok(data) => Self::or_not_connected(
+				data.get_own_connection_id().map(|id| self.get_connection_unwrap(id)),
+			),
+			Err(_) => Err(Error::NotConnected),
+		}
+	}
+
+	/// Get the own connection to the server or `None` if the connection is not
+	/// established yet.
+	pub fn get_own_connection_opt(&self) -> Option<Connection<'a>> {
+		self.get_own_connection().ok()
+	}
+
+	/// Get the number of connected clients and the maximum number of clients
+	/// on this server as `(clients_online, max_clients)`.
+	///
+	/// Both are only available after they were requested with
+	/// [`request_variables`]. The slots that are reserved for clients with
+	/// special permissions are returned by `get_reserved_slots`.
+	///
+	/// [`request_variables`]: #method.request_variables
+	pub fn get_slots_used(&self) -> Result<(i32, i32), Error> {
+		Ok((self.get_clients_online()?, self.get_max_clients()?))
+	}
+
+	/// Check if the voice data on this server is encrypted.
+	///
+	/// If the server decides the encryption per channel, this checks the
+	/// channel of our own connection.
+	pub fn is_encrypted(&self) -> Result<bool, Error> {
+		match self.get_codec_encryption_mode()? {
+			CodecEncryptionMode::ForcedOn => Ok(true),
+			CodecEncryptionMode::ForcedOff => Ok(false),
+			CodecEncryptionMode::PerChannel => {
+				let channel = self.get_own_connection()?.get_channel()?;
+				channel.get_codec_is_unencrypted().map(|u| !u)
+			}
+		}
+	}
+
+	/// Get all antiflood settings of this server.
+	///
+	/// Most of them are only available after they were requested with
+	/// [`request_variables`].
+	///
+	/// [`request_variables`]: #method.request_variables
+	pub fn get_antiflood(&self) -> Result<AntifloodSettings, Error> {
+		Ok(AntifloodSettings {
+			points_tick_reduce: self.get_antiflood_points_tick_reduce()?,
+			points_needed_command_block: self.get_antiflood_points_needed_command_block()?,
+			points_needed_ip_block: self.get_antiflood_points_needed_ip_block()?,
+			points_needed_plugin_block: self.get_antiflood_points_needed_plugin_block()?,
+		})
+	}
+
+	/// Get the version, the platform and the lowest allowed client version
+	/// of this server.
+	///
+	/// The lowest allowed client version is only available after it was
+	/// requested with [`request_variables`].
+	///
+	/// [`request_variables`]: #method.request_variables
+	pub fn get_version_info(&self) -> Result<ServerVersionInfo, Error> {
+		Ok(ServerVersionInfo {
+			version: self.get_version()?.to_string(),
+			platform: self.get_platform()?.to_string(),
+			min_client_version: self.get_min_client_version().ok().map(|v| v.to_string()),
+		})
+	}
+
+	/// Get the unique identifier of our own identity.
+	///
+	/// Fails with `Error::NotConnected` if our own connection is not known.
+	pub fn get_own_uid(&self) -> Result<String, Error> {
+		Self::or_not_connected(
+			self.get_own_connection().and_then(|c| c.get_uid().map(|u| u.to_string())),
+		)
+	}
+
+	/// Get the database id of our own identity on this server.
+	///
+	/// Fails with `Error::NotConnected` if our own connection is not known.
+	pub fn get_own_database_id(&self) -> Result<u64, Error> {
+		Self::or_not_connected(self.get_own_connection().and_then(|c| c.get_database_id()))
+	}
+
+	/// Replace the `Error::Ok` of missing data with `Error::NotConnected`.
+	fn or_not_connected<T>(res: Result<T, Error>) -> Result<T, Error> {
+		match res {
+			Err(Error::Ok) => Err(Error::NotConnected),
+			res => res,
 		}
 	}
 
@@ -486,6 +1172,38 @@ impl<'a> Server<'a> {
 		}
 	}
 
+	/// Ask TeamSpeak which connections are in a channel.
+	///
+	/// Unlike the connections that are cached for a channel, this also finds
+	/// connections of channels that are not fully known yet, e.g. because the
+	/// channel is not subscribed.
+	pub fn get_channel_client_list(&self, channel: &Channel) -> Result<Vec<Connection<'a>>, Error> {
+		unsafe {
+			let mut result: *mut u16 = std::ptr::null_mut();
+			let functions = TS3_FUNCTIONS.as_ref().expect("Functions should be loaded");
+			let res: Error = transmute((functions.get_channel_client_list)(
+				self.get_id().0,
+				channel.get_id().0,
+				&mut result,
+			));
+			if res != Error::Ok {
+				return Err(res);
+			}
+			let mut connections = Vec::new();
+			let mut counter = 0;
+			while *result.offset(counter) != 0 {
+				// Connections that are not cached only know their id
+				let connection_id = ConnectionId(*result.offset(counter));
+				connections.push(self.get_connection(connection_id).unwrap_or_else(|| {
+					Connection::new_err(self.api, self.get_id(), connection_id)
+				}));
+				counter += 1;
+			}
+			(functions.free_memory)(result as *mut c_void);
+			Ok(connections)
+		}
+	}
+
 	/// Get the ids of all channels on this server.
 	pub fn get_channels(&self) -> Vec<Channel<'a>> {
 		match self.data {
@@ -497,6 +1215,64 @@ impl<'a> Server<'a> {
 		}
 	}
 
+	/// Get all channels in the order in which TeamSpeak displays them.
+	///
+	/// Every channel is followed by its subchannels. The `order` of a channel is
+	/// the id of the channel above it, channels where this chain is broken are
+	/// appended to their siblings by id.
+	pub fn get_channels_ordered(&self) -> Vec<Channel<'a>> {
+		self.get_channel_tree().into_iter().map(|(c, _)| c).collect()
+	}
+
+	/// Get all channels in the order of [`get_channels_ordered`] together
+	/// with their depth in the channel tree.
+	///
+	/// Top-level channels have the depth zero, their subchannels one and so
+	/// on, e.g. for indenting the channels when showing them.
+	///
+	/// [`get_channels_ordered`]: #method.get_channels_ordered
+	pub fn get_channel_tree(&self) -> Vec<(Channel<'a>, usize)> {
+		let mut channels = Vec::new();
+		self.add_channels_ordered(ChannelId(0), 0, &mut channels);
+		channels
+	}
+
+	fn add_channels_ordered(
+		&self, parent: ChannelId, depth: usize, channels: &mut Vec<(Channel<'a>, usize)>,
+	) {
+		let mut siblings: Vec<_> = match self.data {
+			Ok(data) => data
+				.subchannels
+				.get(&parent)
+				.map_or(Vec::new(), |cs| cs.iter().filter_map(|c| self.get_channel(*c)).collect()),
+			Err(_) => return,
+		};
+		siblings.sort_by_key(|c| c.get_id());
+		let mut previous = ChannelId(0);
+		while !siblings.is_empty() {
+			let next = siblings.iter().position(|c| c.get_order() == Ok(previous)).unwrap_or(0);
+			let channel = siblings.remove(next);
+			previous = channel.get_id();
+			channels.push((channel, depth));
+			self.add_channels_ordered(previous, depth + 1, channels);
+		}
+	}
+
+	/// The number of visible connections on this server.
+	///
+	/// This is cheaper than `get_connections().len()`. The number of all
+	/// connections on the server is available from `get_clients_online`.
+	pub fn connection_count(&self) -> usize {
+		self.data.map_or(0, |data| data.visible_connections.len())
+	}
+
+	/// The number of channels on this server.
+	///
+	/// This is cheaper than `get_channels().len()`.
+	pub fn channel_count(&self) -> usize {
+		self.data.ok().and_then(|data| data.channels.as_ref().ok()).map_or(0, |cs| cs.len())
+	}
+
 	/// Get the connection on this server that has the specified id, returns
 	/// `None` if there is no such connection.
 	pub fn get_connection(&self, connection_id: ConnectionId) -> Option<Connection<'a>> {
@@ -517,20 +1293,419 @@ impl<'a> Server<'a> {
 		})
 	}
 
-	pub fn get_server_group(&self, _server_group_id: ServerGroupId) -> Option<ServerGroup> {
-		todo!()
+	/// Get the visible connection on this server that has the specified unique id,
+	/// returns `None` if there is no such connection.
+	pub fn get_connection_by_uid(&self, uid: &str) -> Option<Connection<'a>> {
+		self.get_connections().into_iter().find(|c| c.get_uid() == Ok(uid))
 	}
 
-	pub fn get_channel_group(&self, _channel_group_id: ChannelGroupId) -> Option<ChannelGroup> {
-		todo!()
+	/// Get all visible connections on this server that have the specified name.
+	pub fn get_connections_by_name(&self, name: &str) -> Vec<Connection<'a>> {
+		self.get_connections().into_iter().filter(|c| c.get_name() == Ok(name)).collect()
 	}
 
-	/// Send a message to the server chat.
-	pub fn send_message<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
-		unsafe {
-			let text = to_cstring!(message.as_ref());
-			let res: Error = transmute((TS3_FUNCTIONS
-				.as_ref()
+	/// Disconnect from this server and connect again with the same address
+	/// and nickname.
+	///
+	/// TeamSpeak closes the connection in the background, so the connection
+	/// is only started again when it reports the disconnect, after
+	/// [`Plugin::connect_status_change`] was called with
+	/// `ConnectStatus::Disconnected`. If possible, the new connection joins
+	/// the channel where our own client is currently in.
+	///
+	/// TeamSpeak does not give plugins access to the identity, so an empty
+	/// identity is passed and TeamSpeak chooses the identity, usually its
+	/// default one. This is not necessarily the identity of the closed
+	/// connection.
+	///
+	/// Returns an error if the connection info cannot be read or the
+	/// connection cannot be closed, then the connection is not changed. If
+	/// connecting again fails, the error is logged and the server stays
+	/// disconnected.
+	///
+	/// [`Plugin::connect_status_change`]: plugin/trait.Plugin.html#method.connect_status_change
+	pub fn reconnect(&self) -> Result<(), Error> {
+		const SIZE: usize = 1024;
+		let mut host = vec![0 as c_char; SIZE];
+		let mut password = vec![0 as c_char; SIZE];
+		let mut port: u16 = 0;
+		let (host, server_password) = unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.get_server_connect_info)(
+				self.get_id().0,
+				host.as_mut_ptr(),
+				&mut port,
+				password.as_mut_ptr(),
+				SIZE - 1,
+			));
+			if res != Error::Ok {
+				return Err(res);
+			}
+			(to_string!(host.as_ptr()), to_string!(password.as_ptr()))
+		};
+		let own_connection = self.get_own_connection()?;
+		let nickname = own_connection.get_name()?.to_string();
+		let (channel_path, channel_password) = own_connection
+			.get_channel()
+			.and_then(|c| c.get_connect_info())
+			.unwrap_or_else(|_| (String::new(), None));
+		let reconnect = Reconnect {
+			host,
+			port,
+			nickname,
+			channel_path,
+			channel_password: channel_password.unwrap_or_default(),
+			server_password,
+		};
+
+		// Store it first, the disconnect could be reported at once
+		self.api.reconnects.borrow_mut().insert(self.get_id(), reconnect);
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.stop_connection)(self.get_id().0, std::ptr::null()));
+			match res {
+				Error::Ok => Ok(()),
+				_ => {
+					self.api.reconnects.borrow_mut().remove(&self.get_id());
+					Err(res)
+				}
+			}
+		}
+	}
+
+	/// Ask TeamSpeak to fetch the server properties that are only available
+	/// on request, e.g. the uptime and the creation time.
+	pub fn request_variables(&self) -> Result<(), Error> {
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_server_variables)(self.get_id().0));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Ask the server for all connections that belong to a unique identifier.
+	///
+	/// The results are reported to [`Plugin::client_ids`] and
+	/// [`Plugin::client_ids_finished`].
+	///
+	/// [`Plugin::client_ids`]: plugin/trait.Plugin.html#method.client_ids
+	/// [`Plugin::client_ids_finished`]: plugin/trait.Plugin.html#method.client_ids_finished
+	pub fn request_client_ids(&self, uid: &str) -> Result<(), Error> {
+		unsafe {
+			let res: Error =
+				transmute((TS3_FUNCTIONS
+					.as_ref()
+					.expect("Functions should be loaded")
+					.request_client_ids)(
+					self.get_id().0, to_cstring!(uid).as_ptr(), std::ptr::null()
+				));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Ask the server for the server groups of a client.
+	///
+	/// This also works for clients that are not visible. The groups are
+	/// reported to [`Plugin::server_groups_by_client`].
+	///
+	/// [`Plugin::server_groups_by_client`]: plugin/trait.Plugin.html#method.server_groups_by_client
+	pub fn request_server_groups_by_client(&self, database_id: u64) -> Result<(), Error> {
+		if let Ok(data) = self.data {
+			data.client_server_groups.borrow_mut().remove(&database_id);
+		}
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_server_groups_by_client_id)(
+				self.get_id().0, database_id, std::ptr::null()
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Start downloading an icon of this server into the directory `dest`.
+	///
+	/// Returns the path of the icon file, it exists when the file transfer
+	/// finished. An existing file gets overwritten. Fails with
+	/// `Error::ParameterInvalid` if `icon_id` is zero, which means that there
+	/// is no icon.
+	pub fn download_icon(&self, icon_id: u64, dest: &Path) -> Result<PathBuf, Error> {
+		if icon_id == 0 {
+			return Err(Error::ParameterInvalid);
+		}
+		// Icons are stored in the root channel
+		let name = format!("icon_{}", icon_id);
+		unsafe {
+			let mut transfer_id: u16 = 0;
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_file)(
+				self.get_id().0,
+				0,
+				to_cstring!("").as_ptr(),
+				to_cstring!(format!("/{}", name)).as_ptr(),
+				1,
+				0,
+				to_cstring!(dest.to_string_lossy().as_ref()).as_ptr(),
+				&mut transfer_id,
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(dest.join(name)),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Ask the server for the files and directories in `path` of a channel.
+	///
+	/// `path` starts with a `/`, the root directory of the channel is `/`.
+	/// Use `None` as password if the channel has no password. When the list
+	/// arrived, [`Plugin::file_list`] is called with all entries.
+	///
+	/// [`Plugin::file_list`]: plugin/trait.Plugin.html#method.file_list
+	pub fn request_file_list(
+		&self, channel: &Channel, password: Option<&str>, path: &str,
+	) -> Result<(), Error> {
+		// Forget entries of an earlier request that did not finish
+		if let Ok(data) = self.data {
+			data.file_lists.borrow_mut().remove(&(channel.get_id(), path.to_string()));
+		}
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_file_list)(
+				self.get_id().0,
+				channel.get_id().0,
+				to_cstring!(password.unwrap_or("")).as_ptr(),
+				to_cstring!(path).as_ptr(),
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Subscribe to all channels on this server, so all connections are visible.
+	pub fn subscribe_all(&self) -> Result<(), Error> {
+		unsafe {
+			let res: Error =
+				transmute((TS3_FUNCTIONS
+					.as_ref()
+					.expect("Functions should be loaded")
+					.request_channel_subscribe_all)(self.get_id().0, std::ptr::null()));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Unsubscribe from all channels on this server.
+	pub fn unsubscribe_all(&self) -> Result<(), Error> {
+		unsafe {
+			let res: Error =
+				transmute((TS3_FUNCTIONS
+					.as_ref()
+					.expect("Functions should be loaded")
+					.request_channel_unsubscribe_all)(self.get_id().0, std::ptr::null()));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// The time when this server was created.
+	///
+	/// TeamSpeak only knows the creation time after it was requested with
+	/// [`request_variables`]. Otherwise it is computed from the uptime,
+	/// if that is not known either, `Error::Ok` is returned.
+	///
+	/// [`request_variables`]: #method.request_variables
+	pub fn get_created(&self) -> Result<Timestamp, Error> {
+		let id = self.get_id();
+		let created = ServerData::get_property_as_uint64(id, VirtualServerProperties::Created)?;
+		if created != 0 {
+			return time::timestamp_from_secs(created).ok_or(Error::ParameterInvalid);
+		}
+		match ServerData::get_property_as_uint64(id, VirtualServerProperties::Uptime)? {
+			0 => Err(Error::Ok),
+			uptime => Ok(time::ago(time::duration_from_secs(uptime))),
+		}
+	}
+
+	/// Get all visible connections on this server that are currently talking.
+	pub fn get_talking_connections(&self) -> Vec<Connection<'a>> {
+		self.get_connections()
+			.into_iter()
+			.filter(|c| c.get_talking() == Ok(TalkStatus::Talking))
+			.collect()
+	}
+
+	/// Get everyone who is currently whispering to our own client.
+	///
+	/// This uses the cached whisper state that is kept up to date by the talk
+	/// status events, so it does not query TeamSpeak for every connection.
+	pub fn whisper_targets(&self) -> Vec<Connection<'a>> {
+		self.get_connections().into_iter().filter(|c| c.is_whispering_to_me()).collect()
+	}
+
+	/// Read a server property as string directly from TeamSpeak.
+	///
+	/// This is a fallback for properties that are not exposed through a getter
+	/// yet, prefer the typed getters where they exist.
+	pub fn get_raw_variable_string(
+		&self, property: VirtualServerProperties,
+	) -> Result<String, Error> {
+		ServerData::get_property_as_string(self.get_id(), property)
+	}
+
+	/// Read a server property as integer directly from TeamSpeak.
+	///
+	/// This is a fallback for properties that are not exposed through a getter
+	/// yet, prefer the typed getters where they exist.
+	pub fn get_raw_variable_int(&self, property: VirtualServerProperties) -> Result<i32, Error> {
+		ServerData::get_property_as_int(self.get_id(), property)
+	}
+
+	/// Read a server property as 64 bit integer directly from TeamSpeak.
+	///
+	/// This is a fallback for properties that are not exposed through a getter
+	/// yet, prefer the typed getters where they exist.
+	pub fn get_raw_variable_uint64(&self, property: VirtualServerProperties) -> Result<u64, Error> {
+		ServerData::get_property_as_uint64(self.get_id(), property)
+	}
+
+	/// Get a server group of this server.
+	///
+	/// TeamSpeak fetches the server groups when connecting, they can be
+	/// requested again with [`request_server_group_list`]. Returns `None` if
+	/// the group is unknown or the list was not received yet.
+	///
+	/// [`request_server_group_list`]: #method.request_server_group_list
+	pub fn get_server_group(&self, server_group_id: ServerGroupId) -> Option<ServerGroup> {
+		self.data.ok().and_then(|data| data.server_groups.get(&server_group_id)).cloned()
+	}
+
+	/// Ask the server for the list of server groups.
+	pub fn request_server_group_list(&self) -> Result<(), Error> {
+		if let Ok(data) = self.data {
+			data.server_group_list_requested.set(true);
+		}
+		unsafe {
+			let res: Error =
+				transmute((TS3_FUNCTIONS
+					.as_ref()
+					.expect("Functions should be loaded")
+					.request_server_group_list)(self.get_id().0, std::ptr::null()));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Get a channel group of this server.
+	///
+	/// TeamSpeak fetches the channel groups when connecting, they can be
+	/// requested again with [`request_channel_group_list`]. Returns `None` if
+	/// the group is unknown or the list was not received yet.
+	///
+	/// [`request_channel_group_list`]: #method.request_channel_group_list
+	pub fn get_channel_group(&self, channel_group_id: ChannelGroupId) -> Option<ChannelGroup> {
+		self.data.ok().and_then(|data| data.channel_groups.get(&channel_group_id)).cloned()
+	}
+
+	/// Get the permissions that are set for a client in a channel.
+	///
+	/// Returns `None` if they were not requested with
+	/// [`request_channel_client_permissions`] or were not received yet.
+	///
+	/// [`request_channel_client_permissions`]: #method.request_channel_client_permissions
+	pub fn get_channel_client_permissions(
+		&self, channel_id: ChannelId, database_id: u64,
+	) -> Option<&'a [ChannelClientPermission]> {
+		self.data
+			.ok()
+			.and_then(|data| data.channel_client_permissions.get(&(channel_id, database_id)))
+			.map(|p| p.as_slice())
+	}
+
+	/// Ask the server for the permissions that are set for a client in a
+	/// channel.
+	///
+	/// When they arrive, [`Plugin::channel_client_permissions`] is called and
+	/// they are available with [`get_channel_client_permissions`].
+	///
+	/// [`Plugin::channel_client_permissions`]: plugin/trait.Plugin.html#method.channel_client_permissions
+	/// [`get_channel_client_permissions`]: #method.get_channel_client_permissions
+	pub fn request_channel_client_permissions(
+		&self, channel_id: ChannelId, database_id: u64,
+	) -> Result<(), Error> {
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_channel_client_perm_list)(
+				self.get_id().0,
+				channel_id.0,
+				database_id,
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Ask the server for the list of channel groups.
+	pub fn request_channel_group_list(&self) -> Result<(), Error> {
+		if let Ok(data) = self.data {
+			data.channel_group_list_requested.set(true);
+		}
+		unsafe {
+			let res: Error =
+				transmute((TS3_FUNCTIONS
+					.as_ref()
+					.expect("Functions should be loaded")
+					.request_channel_group_list)(self.get_id().0, std::ptr::null()));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Send a message to the server chat.
+	///
+	/// Messages that are too long for TeamSpeak get truncated.
+	pub fn send_message<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
+		unsafe {
+			let text = to_cstring!(TsApi::truncate_message(message.as_ref(), MAX_SIZE_TEXTMESSAGE));
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
 				.expect("Functions should be loaded")
 				.request_send_server_text_msg)(
 				self.get_id().0, text.as_ptr(), std::ptr::null()
@@ -542,21 +1717,99 @@ impl<'a> Server<'a> {
 		}
 	}
 
-	/// Sends a plugin message to all connections on the server.
+	/// Move our own client into a channel.
+	///
+	/// Use `None` as password if the channel has no password. Fails with
+	/// `Error::NotConnected` if our own connection is not known.
+	pub fn join_channel(&self, channel: &Channel, password: Option<&str>) -> Result<(), Error> {
+		let own_id = self.get_own_connection()?.get_id();
+		unsafe {
+			let password = to_cstring!(password.unwrap_or(""));
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_client_move)(
+				self.get_id().0,
+				own_id.0,
+				channel.get_id().0,
+				password.as_ptr(),
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Send a message to the chat of a channel on this server, the channel
+	/// does not have to be cached.
+	///
+	/// Messages that are too long for TeamSpeak get truncated.
+	pub fn send_channel_message<S: AsRef<str>>(
+		&self, channel: &Channel, message: S,
+	) -> Result<(), Error> {
+		unsafe {
+			let text = to_cstring!(TsApi::truncate_message(message.as_ref(), MAX_SIZE_TEXTMESSAGE));
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_send_channel_text_msg)(
+				self.get_id().0,
+				text.as_ptr(),
+				channel.get_id().0,
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Send multiple messages to the server chat.
+	///
+	/// Messages that are longer than TeamSpeak allows are split into several
+	/// messages, preferably at line breaks. Sending stops at the first error.
+	/// Keep in mind that sending many messages at once can trigger the
+	/// anti-flood protection of the server.
+	pub fn send_messages(&self, messages: &[&str]) -> Result<(), Error> {
+		for message in messages {
+			for part in TsApi::split_message(message, MAX_SIZE_TEXTMESSAGE) {
+				self.send_message(part)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Sends a plugin message to the connections selected by `target`.
 	///
 	/// Messages can be received in [`Plugin::plugin_message`].
 	/// This is refered to as `PluginCommand` in TeamSpeak.
 	///
 	/// [`Plugin::plugin_message`]: plugin/trait.Plugin.html#method.plugin_message
-	pub fn send_plugin_message<S: AsRef<str>>(&self, message: S) {
+	pub fn send_plugin_message<S: AsRef<str>>(&self, message: S, target: PluginTarget) {
+		let (mode, ids) = match target {
+			PluginTarget::Server => (PluginTargetMode::Server, None),
+			PluginTarget::CurrentChannel => (PluginTargetMode::CurrentChannel, None),
+			PluginTarget::CurrentChannelSubscribed => {
+				(PluginTargetMode::CurrentChannelSubscribedClients, None)
+			}
+			PluginTarget::Clients(ids) => {
+				// The list of target ids is terminated by a zero
+				let ids: Vec<u16> = ids.iter().map(|id| id.0).chain(Some(0)).collect();
+				(PluginTargetMode::Client, Some(ids))
+			}
+		};
+		let target_ids = ids.as_ref().map_or(std::ptr::null(), |ids| ids.as_ptr());
 		unsafe {
 			let text = to_cstring!(message.as_ref());
 			(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").send_plugin_command)(
 				self.get_id().0,
 				to_cstring!(self.api.get_plugin_id()).as_ptr(),
 				text.as_ptr(),
-				PluginTargetMode::Server as i32,
-				std::ptr::null(),
+				mode as i32,
+				target_ids,
 				std::ptr::null(),
 			);
 		}
@@ -564,6 +1817,12 @@ impl<'a> Server<'a> {
 
 	/// Print a message into the server or channel tab of this server. This is only
 	/// visible in the window of this client and will not be sent to the server.
+	///
+	/// The message is rendered as BBCode, see [`TsApi::bbcode_color`] and
+	/// [`TsApi::escape_bbcode`].
+	///
+	/// [`TsApi::bbcode_color`]: struct.TsApi.html#method.bbcode_color
+	/// [`TsApi::escape_bbcode`]: struct.TsApi.html#method.escape_bbcode
 	pub fn print_message<S: AsRef<str>>(&self, message: S, target: MessageTarget) {
 		unsafe {
 			let text = to_cstring!(message.as_ref());
@@ -574,6 +1833,77 @@ impl<'a> Server<'a> {
 			);
 		}
 	}
+
+	/// Use the custom device, which was registered with
+	/// [`TsApi::register_custom_device`], as capture device for this server.
+	///
+	/// [`TsApi::register_custom_device`]: struct.TsApi.html#method.register_custom_device
+	pub fn open_custom_capture_device(&self) -> Result<(), Error> {
+		let device = self.api.custom_device.borrow();
+		let device_id = match *device {
+			Some((ref id, _)) => id,
+			None => return Err(Error::SoundUnknownDevice),
+		};
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.open_capture_device)(
+				self.get_id().0,
+				to_cstring!("custom").as_ptr(),
+				to_cstring!(device_id.as_str()).as_ptr(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Send voice data through the custom capture device of this plugin.
+	///
+	/// The custom device has to be opened for this server with
+	/// [`open_custom_capture_device`] before. The samples are sent like
+	/// normally recorded sound, so the current whisper list is respected.
+	///
+	/// The voice data has to be 16 bit with 48 kHz. The channels are packed
+	/// (interleaved) and `channels` has to match the channel count of the
+	/// registered device.
+	///
+	/// [`open_custom_capture_device`]: #method.open_custom_capture_device
+	pub fn send_voice_samples(&self, samples: &[i16], channels: i32) -> Result<(), Error> {
+		let device = self.api.custom_device.borrow();
+		let device_id = match *device {
+			Some((ref id, device_channels)) => {
+				if device_channels != channels {
+					return Err(Error::SoundInvalidChannelCount);
+				}
+				id
+			}
+			None => return Err(Error::SoundUnknownDevice),
+		};
+		if channels <= 0 {
+			return Err(Error::SoundInvalidChannelCount);
+		}
+		let frames = samples.len() / channels as usize;
+		if frames * channels as usize != samples.len() {
+			return Err(Error::ParameterInvalidSize);
+		}
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.process_custom_capture_data)(
+				to_cstring!(device_id.as_str()).as_ptr(),
+				samples.as_ptr(),
+				frames as c_int,
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
 }
 
 // ********** Channel **********
@@ -644,7 +1974,7 @@ impl ChannelData {
 	/// Get a channel property that is stored as an uint64.
 	fn get_property_as_uint64(
 		server_id: ServerId, id: ChannelId, property: ChannelProperties,
-	) -> Result<i32, Error> {
+	) -> Result<u64, Error> {
 		unsafe {
 			let mut number: u64 = 0;
 			let res: Error = transmute((TS3_FUNCTIONS
@@ -654,7 +1984,7 @@ impl ChannelData {
 				server_id.0, id.0, property as usize, &mut number
 			));
 			match res {
-				Error::Ok => Ok(number as i32),
+				Error::Ok => Ok(number),
 				_ => Err(res),
 			}
 		}
@@ -701,6 +2031,35 @@ impl<'a> Channel<'a> {
 	/// Get the server of this channel.
 	pub fn get_server(&self) -> Server<'a> { self.api.get_server_unwrap(self.get_server_id()) }
 
+	/// Get the direct subchannels of this channel.
+	pub fn get_subchannels(&self) -> Vec<Channel<'a>> {
+		let server = self.get_server();
+		match server.data {
+			Ok(data) => data.subchannels.get(&self.get_id()).map_or(Vec::new(), |cs| {
+				cs.iter().filter_map(|c| server.get_channel(*c)).collect()
+			}),
+			Err(_) => Vec::new(),
+		}
+	}
+
+	/// Get all visible connections in this channel.
+	pub fn get_connections(&self) -> Vec<Connection<'a>> {
+		let id = self.get_id();
+		self.get_server()
+			.get_connections()
+			.into_iter()
+			.filter(|c| c.data.ok().and_then(|d| d.get_channel_id().ok()) == Some(id))
+			.collect()
+	}
+
+	/// If there are no connections in this channel.
+	///
+	/// Only visible connections are known, so this is also `true` if we are
+	/// not subscribed to the channel, see [`get_subscribed`].
+	///
+	/// [`get_subscribed`]: #method.get_subscribed
+	pub fn is_empty(&self) -> bool { self.get_connections().is_empty() }
+
 	pub fn get_parent_channel(&self) -> Result<Option<Channel<'a>>, Error> {
 		match self.data {
 			Ok(data) => data.get_parent_channel_id().map(|parent_channel_id| {
@@ -714,17 +2073,113 @@ impl<'a> Channel<'a> {
 		}
 	}
 
-	/// Send a message to this channel chat.
-	pub fn send_message<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
+	/// Get the channel above this channel among its siblings.
+	///
+	/// Returns `None` if this is the first subchannel of its parent and
+	/// `Error::ChannelInvalidId` if the channel above is not cached.
+	pub fn get_order_predecessor(&self) -> Result<Option<Channel<'a>>, Error> {
+		let order = self.get_order()?;
+		if order.0 == 0 {
+			return Ok(None);
+		}
+		self.get_server().get_channel(order).map(Some).ok_or(Error::ChannelInvalidId)
+	}
+
+	/// Get the maximum number of clients in this channel.
+	///
+	/// Returns `None` if the number of clients is unlimited.
+	pub fn get_effective_max_clients(&self) -> Result<Option<i32>, Error> {
+		if self.get_max_clients_unlimited()? { Ok(None) } else { self.get_max_clients().map(Some) }
+	}
+
+	/// Get the information that is needed to join this channel, e.g. for a
+	/// link to this channel.
+	///
+	/// Returns the path of this channel and the channel password if it is
+	/// known.
+	pub fn get_connect_info(&self) -> Result<(String, Option<String>), Error> {
+		const SIZE: usize = 1024;
+		let mut path = vec![0 as c_char; SIZE];
+		let mut password = vec![0 as c_char; SIZE];
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.get_channel_connection_info)(
+				self.get_server_id().0,
+				self.get_id().0,
+				path.as_mut_ptr(),
+				password.as_mut_ptr(),
+				SIZE - 1,
+			));
+			match res {
+				Error::Ok => {
+					let password = to_string!(password.as_ptr());
+					Ok((
+						to_string!(path.as_ptr()),
+						if password.is_empty() { None } else { Some(password) },
+					))
+				}
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Ask the server for the description of this channel.
+	///
+	/// When the description arrives, it is stored in this channel and
+	/// [`Plugin::channel_description_updated`] gets called, afterwards it
+	/// can be read with `get_description`.
+	///
+	/// [`Plugin::channel_description_updated`]: plugin/trait.Plugin.html#method.channel_description_updated
+	pub fn request_description(&self) -> Result<(), Error> {
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_channel_description)(
+				self.get_server_id().0, self.get_id().0, std::ptr::null()
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Send a message to this channel chat.
+	///
+	/// Messages that are too long for TeamSpeak get truncated.
+	pub fn send_message<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
+		self.get_server().send_channel_message(self, message)
+	}
+
+	/// If this channel is protected by a password.
+	pub fn has_password(&self) -> bool { self.get_password().unwrap_or(false) }
+
+	/// Change the password of this channel, `None` removes the password.
+	///
+	/// This needs the permission `b_channel_modify_password`. When the
+	/// server applied the change, [`Plugin::channel_password_updated`] gets
+	/// called.
+	///
+	/// [`Plugin::channel_password_updated`]: plugin/trait.Plugin.html#method.channel_password_updated
+	pub fn set_password(&self, password: Option<&str>) -> Result<(), Error> {
 		unsafe {
-			let text = to_cstring!(message.as_ref());
-			let res: Error = transmute((TS3_FUNCTIONS
-				.as_ref()
-				.expect("Functions should be loaded")
-				.request_send_channel_text_msg)(
-				self.data.unwrap().server_id.0,
-				text.as_ptr(),
-				self.data.unwrap().id.0,
+			let password = to_cstring!(password.unwrap_or(""));
+			let functions = TS3_FUNCTIONS.as_ref().expect("Functions should be loaded");
+			let res: Error = transmute((functions.set_channel_variable_as_string)(
+				self.get_server_id().0,
+				self.get_id().0,
+				ChannelProperties::Password as usize,
+				password.as_ptr(),
+			));
+			if res != Error::Ok {
+				return Err(res);
+			}
+			let res: Error = transmute((functions.flush_channel_updates)(
+				self.get_server_id().0,
+				self.get_id().0,
 				std::ptr::null(),
 			));
 			match res {
@@ -800,6 +2255,17 @@ impl ConnectionData {
 		}
 	}
 
+	/// Get a connection property that contains a port.
+	///
+	/// TeamSpeak stores it as a uint64, a value that is not a valid port is
+	/// reported as `Error::ParameterInvalid`.
+	fn get_connection_property_as_port(
+		server_id: ServerId, id: ConnectionId, property: ConnectionProperties,
+	) -> Result<u16, Error> {
+		Self::get_connection_property_as_uint64(server_id, id, property)
+			.and_then(|p| u16::try_from(p).map_err(|_| Error::ParameterInvalid))
+	}
+
 	/// Get a connection property that is stored as a double.
 	fn get_connection_property_as_double(
 		server_id: ServerId, id: ConnectionId, property: ConnectionProperties,
@@ -872,6 +2338,24 @@ impl ConnectionData {
 		}
 	}
 
+	/// Ask the TeamSpeak api about the name that is displayed for a connection.
+	fn query_display_name(server_id: ServerId, id: ConnectionId) -> Result<String, Error> {
+		const SIZE: usize = 512;
+		let mut buf = vec![0 as c_char; SIZE];
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.get_client_display_name)(
+				server_id.0, id.0, buf.as_mut_ptr(), SIZE - 1
+			));
+			match res {
+				Error::Ok => Ok(to_string!(buf.as_ptr())),
+				_ => Err(res),
+			}
+		}
+	}
+
 	/// Ask the TeamSpeak api, if the specified connection is currently whispering to our own
 	/// client.
 	fn query_whispering(server_id: ServerId, id: ConnectionId) -> Result<bool, Error> {
@@ -923,41 +2407,648 @@ impl<'a> Connection<'a> {
 		}
 	}
 
-	pub fn get_channel_group_inherited_channel(&self) -> Result<Channel<'a>, Error> {
-		match self.data {
-			Ok(data) => data
-				.get_channel_group_inherited_channel_id()
-				.map(|c| self.get_server().get_channel_unwrap(c)),
-			Err(_) => Err(Error::Ok),
+	/// Get the channel group of this connection.
+	///
+	/// The group applies in the channel returned by
+	/// [`get_channel_group_inherited_channel`], which is the current channel
+	/// or one of its parents. If the channel group list was not received yet,
+	/// it gets requested once and `None` is returned.
+	///
+	/// [`get_channel_group_inherited_channel`]: #method.get_channel_group_inherited_channel
+	pub fn get_channel_group(&self) -> Option<ChannelGroup> {
+		let server = self.get_server();
+		if let Ok(data) = server.data {
+			if data.channel_groups.is_empty() {
+				if !data.channel_group_list_requested.get() {
+					let _ = server.request_channel_group_list();
+				}
+				return None;
+			}
+		}
+		self.get_channel_group_id().ok().and_then(|g| server.get_channel_group(g))
+	}
+
+	/// Get the channel from which the channel group of this connection is
+	/// inherited.
+	///
+	/// Returns `None` if TeamSpeak reports no channel, which means that the
+	/// channel group is not inherited.
+	pub fn get_channel_group_inherited_channel(&self) -> Result<Option<Channel<'a>>, Error> {
+		match self.data {
+			Ok(data) => data.get_channel_group_inherited_channel_id().map(|c| {
+				if c.0 == 0 { None } else { Some(self.get_server().get_channel_unwrap(c)) }
+			}),
+			Err(_) => Err(Error::Ok),
+		}
+	}
+
+	/*/// The connection properties that are only available for our own client.
+	pub fn get_own_data(&self) -> Option<&OwnConnectionData> {
+		self.data.ok().and_then(|data| data.own_data.as_ref())
+	}
+
+	/// The connection properties that are only available for server queries.
+	pub fn get_serverquery_data(&self) -> Option<&ServerqueryConnectionData> {
+		self.data.ok().and_then(|data| data.serverquery_data.as_ref())
+	}
+
+	/// The connection properties that are only available on request.
+	pub fn get_optional_data(&self) -> Option<&OptionalConnectionData> {
+		self.data.ok().map(|data| &data.optional_data)
+	}*/
+
+	/// If this connection is marked as away.
+	pub fn is_away(&self) -> bool { self.get_away() == Ok(AwayStatus::Zzz) }
+
+	/// If the microphone of this connection is muted.
+	///
+	/// Returns `false` if the mute state is unknown.
+	pub fn is_input_muted(&self) -> bool { self.get_input_muted() == Ok(MuteInputStatus::Muted) }
+
+	/// If the speakers of this connection are muted.
+	///
+	/// Returns `false` if the mute state is unknown.
+	pub fn is_output_muted(&self) -> bool { self.get_output_muted() == Ok(MuteOutputStatus::Muted) }
+
+	/// If this connection is currently whispering to our own client.
+	///
+	/// Returns `false` if the whisper state is unknown.
+	pub fn is_whispering_to_me(&self) -> bool { self.get_whispering() == Ok(true) }
+
+	/// Get the names of the server groups of this connection.
+	///
+	/// Groups that are not known are left out. If the server group list was not
+	/// received yet, it gets requested once and an empty list is returned.
+	pub fn get_server_group_names(&self) -> Vec<String> {
+		let server = self.get_server();
+		if let Ok(data) = server.data {
+			if data.server_groups.is_empty() {
+				if !data.server_group_list_requested.get() {
+					let _ = server.request_server_group_list();
+				}
+				return Vec::new();
+			}
+		}
+		self.get_server_groups().map_or(Vec::new(), |groups| {
+			groups.iter().filter_map(|g| server.get_server_group(*g)).map(|g| g.name).collect()
+		})
+	}
+
+	/// Ask the server for the server groups of this connection.
+	///
+	/// The groups are reported to [`Plugin::server_groups_by_client`].
+	///
+	/// [`Plugin::server_groups_by_client`]: plugin/trait.Plugin.html#method.server_groups_by_client
+	pub fn request_server_groups(&self) -> Result<(), Error> {
+		self.get_server().request_server_groups_by_client(self.get_database_id()?)
+	}
+
+	/// If this connection is currently recording.
+	///
+	/// Returns `false` if the recording state is unknown.
+	pub fn is_recording(&self) -> bool { self.get_recording() == Ok(true) }
+
+	/// Ask the server for the connection info of this connection, e.g. the
+	/// ping and idle time.
+	///
+	/// [`Plugin::connection_info`] gets called when the info arrives.
+	///
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn request_connection_info(&self) -> Result<(), Error> {
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_connection_info)(
+				self.get_server_id().0, self.get_id().0, std::ptr::null()
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Ask the server for the client properties of this connection that are
+	/// only available on request, e.g. the number of total connections.
+	pub fn request_variables(&self) -> Result<(), Error> {
+		if let Ok(data) = self.data {
+			data.variables_requested.set(true);
+		}
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_client_variables)(
+				self.get_server_id().0, self.get_id().0, std::ptr::null()
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Request the variables of this connection and wait until they arrived.
+	///
+	/// Afterwards, getters that request their value automatically like
+	/// [`get_country`] return the fetched values. This waits for at most five
+	/// seconds and fails with `Error::CurrentlyNotPossible` if the variables
+	/// did not arrive.
+	///
+	/// Calling this from a callback always waits for the whole time, because
+	/// TeamSpeak cannot deliver the variables while its main thread is
+	/// blocked, see the [`blocking`] module.
+	/// Only available with the `blocking` feature.
+	///
+	/// [`get_country`]: #method.get_country
+	/// [`blocking`]: blocking/index.html
+	#[cfg(feature = "blocking")]
+	pub fn fetch_variables(&self) -> Result<(), Error> {
+		blocking::wait_for_update(
+			self.get_server_id(),
+			self.get_id(),
+			std::time::Duration::from_secs(5),
+			|| self.request_variables(),
+		)
+	}
+
+	/// Read a property from the connection info and request the connection
+	/// info if it was not fetched yet.
+	fn get_connection_info_property(&self, property: ConnectionProperties) -> Result<u64, Error> {
+		let res = ConnectionData::get_connection_property_as_uint64(
+			self.get_server_id(),
+			self.get_id(),
+			property,
+		);
+		if res == Err(Error::NoCachedConnectionInfo) {
+			self.request_connection_info()?;
+		}
+		res
+	}
+
+	/// Read the connection info of this connection at once.
+	///
+	/// The returned guard provides the values without errors and discards the
+	/// connection info in TeamSpeak when it is dropped. Prefer this over the
+	/// single getters like [`get_ping`] if the values are kept for longer.
+	///
+	/// If the connection info was not fetched yet, it gets requested and
+	/// `Error::NoCachedConnectionInfo` is returned. Try again after
+	/// [`Plugin::connection_info`] was called.
+	///
+	/// [`get_ping`]: #method.get_ping
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn request_scope(&self) -> Result<RequestedData<'_>, Error> { RequestedData::new(self) }
+
+	/// The ping of this connection.
+	///
+	/// If the connection info was not fetched yet or TeamSpeak discarded it,
+	/// it gets requested and `Error::NoCachedConnectionInfo` is returned. Try
+	/// again after [`Plugin::connection_info`] was called.
+	///
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn get_ping(&self) -> Result<Duration, Error> {
+		self.get_connection_info_property(ConnectionProperties::Ping)
+			.map(time::duration_from_millis)
+	}
+
+	/// How long this connection is idle.
+	///
+	/// If the connection info was not fetched yet, it gets requested and
+	/// `Error::NoCachedConnectionInfo` is returned. Try again after
+	/// [`Plugin::connection_info`] was called.
+	///
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn get_idle_time(&self) -> Result<Duration, Error> {
+		self.get_connection_info_property(ConnectionProperties::IdleTime)
+			.map(time::duration_from_secs)
+	}
+
+	/// How long this connection is connected to the server.
+	///
+	/// If the connection info was not fetched yet, it gets requested and
+	/// `Error::NoCachedConnectionInfo` is returned. Try again after
+	/// [`Plugin::connection_info`] was called.
+	///
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn get_connected_time(&self) -> Result<Duration, Error> {
+		self.get_connection_info_property(ConnectionProperties::ConnectedTime)
+			.map(time::duration_from_secs)
+	}
+
+	/// The IP address of this client.
+	///
+	/// Seeing the address of other clients needs the
+	/// `b_client_remoteaddress_view` permission, without it the address is
+	/// empty.
+	///
+	/// If the connection info was not fetched yet, it gets requested and
+	/// `Error::NoCachedConnectionInfo` is returned. Try again after
+	/// [`Plugin::connection_info`] was called.
+	///
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn get_client_ip(&self) -> Result<String, Error> {
+		let res = ConnectionData::get_connection_property_as_string(
+			self.get_server_id(),
+			self.get_id(),
+			ConnectionProperties::ClientIp,
+		);
+		if res == Err(Error::NoCachedConnectionInfo) {
+			self.request_connection_info()?;
+		}
+		res
+	}
+
+	/// The port that this client uses to connect to the server.
+	///
+	/// Like the IP address, it needs the `b_client_remoteaddress_view`
+	/// permission for other clients and is zero without it.
+	///
+	/// If the connection info was not fetched yet, it gets requested and
+	/// `Error::NoCachedConnectionInfo` is returned. Try again after
+	/// [`Plugin::connection_info`] was called.
+	///
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn get_client_port(&self) -> Result<u16, Error> {
+		let res = ConnectionData::get_connection_property_as_port(
+			self.get_server_id(),
+			self.get_id(),
+			ConnectionProperties::ClientPort,
+		);
+		if res == Err(Error::NoCachedConnectionInfo) {
+			self.request_connection_info()?;
+		}
+		res
+	}
+
+	/// How often this client connected to the server.
+	///
+	/// If the value was not fetched yet, it gets requested like
+	/// [`get_country`].
+	///
+	/// [`get_country`]: #method.get_country
+	pub fn get_total_connections(&self) -> Result<i32, Error> {
+		self.check_variables_received()?;
+		ConnectionData::get_client_property_as_int(
+			self.get_server_id(),
+			self.get_id(),
+			ClientProperties::Totalconnections,
+		)
+	}
+
+	/// The country of this client, e.g. `DE`.
+	///
+	/// If the value was not fetched yet, it gets requested with
+	/// [`request_variables`] and `Error::CurrentlyNotPossible` is returned.
+	/// Try again after [`Plugin::connection_properties_changed`] was called
+	/// for this connection. The variables are only requested once, call
+	/// [`request_variables`] to request them again.
+	///
+	/// [`request_variables`]: #method.request_variables
+	/// [`Plugin::connection_properties_changed`]: plugin/trait.Plugin.html#method.connection_properties_changed
+	pub fn get_country(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::Country)
+	}
+
+	/// The badges of this client as sent by TeamSpeak.
+	///
+	/// If the value was not fetched yet, it gets requested like
+	/// [`get_country`].
+	///
+	/// [`get_country`]: #method.get_country
+	pub fn get_badges(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::Badges)
+	}
+
+	/// The myTeamSpeak id of this client, empty if it has no myTeamSpeak
+	/// account.
+	///
+	/// If the value was not fetched yet, it gets requested like
+	/// [`get_country`].
+	///
+	/// [`get_country`]: #method.get_country
+	pub fn get_myteamspeak_id(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::MyteamspeakId)
+	}
+
+	/// The integrations of this client as sent by TeamSpeak.
+	///
+	/// If the value was not fetched yet, it gets requested like
+	/// [`get_country`].
+	///
+	/// [`get_country`]: #method.get_country
+	pub fn get_integrations(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::Integrations)
+	}
+
+	/// Information about the active integrations of this client as sent by
+	/// TeamSpeak.
+	///
+	/// If the value was not fetched yet, it gets requested like
+	/// [`get_country`].
+	///
+	/// [`get_country`]: #method.get_country
+	pub fn get_active_integrations_info(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::ActiveIntegrationsInfo)
+	}
+
+	/// The version of the TeamSpeak client of this connection.
+	///
+	/// This is only known after the variables were fetched with
+	/// [`request_variables`], until then they get requested like
+	/// [`get_country`] and an error is returned.
+	///
+	/// [`request_variables`]: #method.request_variables
+	/// [`get_country`]: #method.get_country
+	pub fn get_version(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::Version)
+	}
+
+	/// The operating system of the TeamSpeak client of this connection, e.g.
+	/// `Windows`.
+	///
+	/// This is only known after the variables were fetched, see
+	/// [`get_version`].
+	///
+	/// [`get_version`]: #method.get_version
+	pub fn get_platform(&self) -> Result<String, Error> {
+		self.get_client_variable_string(ClientProperties::Platform)
+	}
+
+	/// Read a client property which is only available after
+	/// [`request_variables`] and request it if needed.
+	///
+	/// TeamSpeak does not tell if the variables are available, so they count
+	/// as available after an update arrived for a connection whose variables
+	/// were requested. They are only requested once, call
+	/// [`request_variables`] to request them again.
+	///
+	/// [`request_variables`]: #method.request_variables
+	fn get_client_variable_string(&self, property: ClientProperties) -> Result<String, Error> {
+		self.check_variables_received()?;
+		ConnectionData::get_client_property_as_string(self.get_server_id(), self.get_id(), property)
+	}
+
+	/// Returns `Error::CurrentlyNotPossible` if the variables of this
+	/// connection did not arrive yet and requests them once.
+	///
+	/// See [`get_client_variable_string`].
+	///
+	/// [`get_client_variable_string`]: #method.get_client_variable_string
+	fn check_variables_received(&self) -> Result<(), Error> {
+		if let Ok(data) = self.data {
+			if !data.variables_received {
+				self.request_variables_once()?;
+				return Err(Error::CurrentlyNotPossible);
+			}
+		}
+		Ok(())
+	}
+
+	/// Request the variables of this connection if they were not requested
+	/// yet.
+	fn request_variables_once(&self) -> Result<(), Error> {
+		match self.data {
+			Ok(data) if data.variables_requested.get() => Ok(()),
+			_ => self.request_variables(),
+		}
+	}
+
+	/// The time when this connection started talking, `None` if it is not
+	/// talking currently.
+	pub fn talking_since(&self) -> Option<Timestamp> {
+		self.data.ok().and_then(|data| *data.get_talking_since())
+	}
+
+	/// Check if this client can talk in a channel.
+	///
+	/// A client can talk if its talk power is at least the needed talk power
+	/// of the channel or if it was made a talker. If the talk power is not
+	/// known, the client counts as not able to talk in channels that need
+	/// talk power.
+	///
+	/// If it is not known yet if the client is a talker, the variables of
+	/// the connection get requested once with [`request_variables`], so a
+	/// later check includes it.
+	///
+	/// [`request_variables`]: #method.request_variables
+	pub fn can_talk_in(&self, channel: &Channel) -> bool {
+		let needed = channel.get_needed_talk_power().unwrap_or(0);
+		if needed <= 0 || self.get_talk_power().map(|p| p >= needed).unwrap_or(false) {
+			return true;
+		}
+		match self.get_talker() {
+			Ok(talker) => talker,
+			Err(_) => {
+				let _ = self.request_variables_once();
+				false
+			}
+		}
+	}
+
+	/// Check if two connections belong to the same identity.
+	///
+	/// Connection ids get reused after a client leaves, so `==` only tells
+	/// if two connections are the same right now. This compares the unique
+	/// identifiers instead, which stay the same when a client rejoins. If
+	/// the uid of one of the connections is not known or empty, it falls
+	/// back to comparing the connections with `==`.
+	pub fn same_identity(&self, other: &Connection) -> bool {
+		match (self.get_uid(), other.get_uid()) {
+			(Ok(uid), Ok(other_uid)) if !uid.is_empty() && !other_uid.is_empty() => {
+				uid == other_uid
+			}
+			_ => self == other,
+		}
+	}
+
+	/// When this connection requested talk power.
+	///
+	/// Returns `None` if the connection did not request to talk.
+	pub fn get_talk_request_time(&self) -> Result<Option<Timestamp>, Error> {
+		match self.data {
+			Ok(data) => data
+				.get_talk_request()
+				.map(|t| if time::timestamp_as_secs(&t) == 0 { None } else { Some(t) }),
+			Err(_) => Err(Error::Ok),
+		}
+	}
+
+	/// Request talk power in the current channel, e.g. in moderated channels.
+	///
+	/// This is only possible for our own connection, otherwise
+	/// `Error::ParameterInvalid` is returned.
+	pub fn request_talk_power(&self, message: &str) -> Result<(), Error> {
+		self.set_talk_request(true, message)
+	}
+
+	/// Withdraw a request for talk power of our own connection.
+	pub fn cancel_talk_request(&self) -> Result<(), Error> { self.set_talk_request(false, "") }
+
+	fn set_talk_request(&self, request: bool, message: &str) -> Result<(), Error> {
+		let server = self.get_server();
+		if server.get_own_connection().map(|c| c.get_id()) != Ok(self.get_id()) {
+			return Err(Error::ParameterInvalid);
+		}
+		unsafe {
+			let functions = TS3_FUNCTIONS.as_ref().expect("Functions should be loaded");
+			let res: Error = transmute((functions.set_client_self_variable_as_int)(
+				server.get_id().0,
+				ClientProperties::TalkRequest as usize,
+				request as c_int,
+			));
+			if res != Error::Ok {
+				return Err(res);
+			}
+			let message = to_cstring!(message);
+			let res: Error = transmute((functions.set_client_self_variable_as_string)(
+				server.get_id().0,
+				ClientProperties::TalkRequestMsg as usize,
+				message.as_ptr(),
+			));
+			if res != Error::Ok {
+				return Err(res);
+			}
+			let res: Error = transmute((functions.flush_client_self_updates)(
+				server.get_id().0,
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Change the description of this connection.
+	///
+	/// Changing the description of other clients needs the permission
+	/// `b_client_modify_description`.
+	pub fn set_description(&self, text: &str) -> Result<(), Error> {
+		unsafe {
+			let text = to_cstring!(text);
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_client_edit_description)(
+				self.get_server_id().0,
+				self.get_id().0,
+				text.as_ptr(),
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Kick this connection from its channel into the default channel.
+	///
+	/// The cached data is not changed here, it is updated when the server
+	/// reports the kick and [`Plugin::channel_kick`] is called, so the
+	/// connection is not removed twice.
+	///
+	/// [`Plugin::channel_kick`]: plugin/trait.Plugin.html#method.channel_kick
+	pub fn kick_from_channel(&self, reason: &str) -> Result<(), Error> {
+		unsafe {
+			let reason = to_cstring!(reason);
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_client_kick_from_channel)(
+				self.get_server_id().0,
+				self.get_id().0,
+				reason.as_ptr(),
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Kick this connection from the server.
+	///
+	/// The connection stays cached until the server reports the kick and
+	/// [`Plugin::server_kick`] is called, the connection is removed
+	/// afterwards.
+	///
+	/// [`Plugin::server_kick`]: plugin/trait.Plugin.html#method.server_kick
+	pub fn kick_from_server(&self, reason: &str) -> Result<(), Error> {
+		unsafe {
+			let reason = to_cstring!(reason);
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_client_kick_from_server)(
+				self.get_server_id().0,
+				self.get_id().0,
+				reason.as_ptr(),
+				std::ptr::null(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
 		}
 	}
 
-	/*/// The connection properties that are only available for our own client.
-	pub fn get_own_data(&self) -> Option<&OwnConnectionData> {
-		self.data.ok().and_then(|data| data.own_data.as_ref())
+	/// Read a client property as string directly from TeamSpeak.
+	///
+	/// This is only meant as a fallback for properties that have no getter
+	/// yet, e.g. newly added ones. Prefer the typed getters where they exist.
+	pub fn get_raw_client_variable_string(
+		&self, property: ClientProperties,
+	) -> Result<String, Error> {
+		ConnectionData::get_client_property_as_string(self.get_server_id(), self.get_id(), property)
 	}
 
-	/// The connection properties that are only available for server queries.
-	pub fn get_serverquery_data(&self) -> Option<&ServerqueryConnectionData> {
-		self.data.ok().and_then(|data| data.serverquery_data.as_ref())
+	/// Read a client property as integer directly from TeamSpeak.
+	///
+	/// This is only meant as a fallback for properties that have no getter
+	/// yet, e.g. newly added ones. Prefer the typed getters where they exist.
+	pub fn get_raw_client_variable_int(&self, property: ClientProperties) -> Result<i32, Error> {
+		ConnectionData::get_client_property_as_int(self.get_server_id(), self.get_id(), property)
 	}
 
-	/// The connection properties that are only available on request.
-	pub fn get_optional_data(&self) -> Option<&OptionalConnectionData> {
-		self.data.ok().map(|data| &data.optional_data)
-	}*/
+	/// Move this connection into another channel, the returned request
+	/// resolves when the server answered.
+	///
+	/// Use an empty password if the channel has no password.
+	/// Only available with the `async` feature.
+	#[cfg(feature = "async")]
+	pub fn move_to_channel_async(&self, channel: &Channel, password: &str) -> request::Request {
+		let server_id = self.get_server_id();
+		request::Request::send(self.api.get_plugin_id(), server_id, |return_code| unsafe {
+			transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.request_client_move)(
+				server_id.0,
+				self.get_id().0,
+				channel.get_id().0,
+				to_cstring!(password).as_ptr(),
+				return_code,
+			))
+		})
+	}
 
 	/// Send a private message to this connection.
+	///
+	/// Messages that are too long for TeamSpeak get truncated.
 	pub fn send_message<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
 		unsafe {
-			let text = to_cstring!(message.as_ref());
+			let text = to_cstring!(TsApi::truncate_message(message.as_ref(), MAX_SIZE_TEXTMESSAGE));
 			let res: Error = transmute((TS3_FUNCTIONS
 				.as_ref()
 				.expect("Functions should be loaded")
 				.request_send_private_text_msg)(
-				self.data.unwrap().server_id.0,
+				self.get_server_id().0,
 				text.as_ptr(),
-				self.data.unwrap().id.0,
+				self.get_id().0,
 				std::ptr::null(),
 			));
 			match res {
@@ -968,6 +3059,69 @@ impl<'a> Connection<'a> {
 	}
 }
 
+// ********** RequestedData **********
+/// The connection info of a connection, read at one point in time.
+///
+/// TeamSpeak only keeps the connection info for a while after it was
+/// requested, reading it later fails or returns outdated values. This guard
+/// reads all values at once, so the getters cannot fail. When it is dropped,
+/// the connection info is discarded in TeamSpeak, so the next
+/// [`Connection::request_scope`] fetches fresh values.
+///
+/// [`Connection::request_scope`]: struct.Connection.html#method.request_scope
+pub struct RequestedData<'a> {
+	connection: &'a Connection<'a>,
+	ping: Duration,
+	idle_time: Duration,
+	connected_time: Duration,
+	bytes_sent_total: u64,
+	bytes_received_total: u64,
+}
+
+impl<'a> RequestedData<'a> {
+	fn new(connection: &'a Connection<'a>) -> Result<RequestedData<'a>, Error> {
+		Ok(RequestedData {
+			connection,
+			ping: connection.get_ping()?,
+			idle_time: connection.get_idle_time()?,
+			connected_time: connection.get_connected_time()?,
+			bytes_sent_total: connection
+				.get_connection_info_property(ConnectionProperties::BytesSentTotal)?,
+			bytes_received_total: connection
+				.get_connection_info_property(ConnectionProperties::BytesReceivedTotal)?,
+		})
+	}
+
+	/// Get the connection of this data.
+	pub fn get_connection(&self) -> &Connection<'a> { self.connection }
+
+	/// The ping of the connection.
+	pub fn get_ping(&self) -> Duration { self.ping }
+
+	/// How long the connection was idle.
+	pub fn get_idle_time(&self) -> Duration { self.idle_time }
+
+	/// How long the connection was connected to the server.
+	pub fn get_connected_time(&self) -> Duration { self.connected_time }
+
+	/// The number of bytes the connection sent to the server.
+	pub fn get_bytes_sent_total(&self) -> u64 { self.bytes_sent_total }
+
+	/// The number of bytes the connection received from the server.
+	pub fn get_bytes_received_total(&self) -> u64 { self.bytes_received_total }
+}
+
+impl<'a> Drop for RequestedData<'a> {
+	fn drop(&mut self) {
+		unsafe {
+			(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").clean_up_connection_info)(
+				self.connection.get_server_id().0,
+				self.connection.get_id().0,
+			);
+		}
+	}
+}
+
 pub struct TsApiLock {
 	guard: MutexGuard<'static, (Option<(TsApi, Box<dyn Plugin>)>, Option<String>)>,
 }
@@ -990,6 +3144,88 @@ impl DerefMut for PluginLock {
 	fn deref_mut(&mut self) -> &mut Self::Target { &mut *self.guard.0.as_mut().unwrap().1 }
 }
 
+// ********** EventLog **********
+/// The recently recorded events.
+struct EventLog {
+	capacity: usize,
+	events: VecDeque<(Timestamp, Event)>,
+}
+
+/// The data to connect to a server again, see [`Server::reconnect`].
+///
+/// [`Server::reconnect`]: struct.Server.html#method.reconnect
+struct Reconnect {
+	host: String,
+	port: u16,
+	nickname: String,
+	/// The path of the channel to join, separated by `/`.
+	channel_path: String,
+	channel_password: String,
+	server_password: String,
+}
+
+impl Reconnect {
+	/// Start the connection on the connection handler of a server.
+	fn start(&self, server_id: ServerId) -> Result<(), Error> {
+		// The channel path is passed as list of channel names that ends with
+		// an empty string.
+		let mut channels: Vec<CString> = self
+			.channel_path
+			.split('/')
+			.filter(|c| !c.is_empty())
+			.map(|c| to_cstring!(c))
+			.collect();
+		channels.push(to_cstring!(""));
+		let channel_ptrs: Vec<*const c_char> = channels.iter().map(|c| c.as_ptr()).collect();
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.start_connection)(
+				server_id.0,
+				to_cstring!("").as_ptr(),
+				to_cstring!(self.host.as_str()).as_ptr(),
+				self.port as c_uint,
+				to_cstring!(self.nickname.as_str()).as_ptr(),
+				channel_ptrs.as_ptr(),
+				to_cstring!(self.channel_password.as_str()).as_ptr(),
+				to_cstring!(self.server_password.as_str()).as_ptr(),
+			));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+}
+
+// ********** Timer **********
+/// Identifies a timer that was registered with [`TsApi::schedule`].
+///
+/// [`TsApi::schedule`]: struct.TsApi.html#method.schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+/// The closure of a timer.
+type TimerFn = dyn FnMut(&mut TsApi) + Send;
+
+/// A closure that is called repeatedly by the timer thread.
+struct Timer {
+	id: TimerId,
+	interval: std::time::Duration,
+	/// When the closure should be called the next time.
+	next: std::time::Instant,
+	/// The closure, it is taken out while it is called.
+	f: Option<Box<TimerFn>>,
+}
+
+/// A change of the afk state of a connection.
+#[derive(Debug, PartialEq, Eq)]
+enum AfkChange {
+	WentAfk,
+	Returned,
+}
+
 // ********** TsApi **********
 /// The main struct that contains all permanently save data.
 pub struct TsApi {
@@ -997,13 +3233,45 @@ pub struct TsApi {
 	servers: Map<ServerId, ServerData>,
 	/// The plugin id from TeamSpeak.
 	plugin_id: String,
+	/// The name of the plugin as it is shown in TeamSpeak.
+	plugin_name: String,
+	/// The id and channel count of the custom capture device registered by this plugin.
+	custom_device: RefCell<Option<(String, i32)>>,
+	/// The receiving end of the audio buffer, if it is enabled.
+	audio_receiver: RefCell<Option<Receiver<AudioPacket>>>,
+	/// The idle time after which connections count as away from keyboard.
+	afk_threshold: RefCell<Option<Duration>>,
+	/// The connections that are currently away from keyboard.
+	afk_connections: RefCell<HashSet<(ServerId, ConnectionId)>>,
+	/// The servers that are connected again when they are disconnected.
+	reconnects: RefCell<Map<ServerId, Reconnect>>,
+	/// The recorded events, if the event log is enabled.
+	event_log: RefCell<Option<EventLog>>,
+	/// The registered timers.
+	timers: RefCell<Vec<Timer>>,
+	/// The id for the next timer.
+	next_timer_id: Cell<u64>,
 }
 
 // Don't provide a default Implementation because we don't want the TsApi
 // to be publicly constructable.
 impl TsApi {
 	/// Create a new TsApi instance without loading anything.
-	fn new(plugin_id: String) -> TsApi { TsApi { servers: Map::new(), plugin_id: plugin_id } }
+	fn new(plugin_id: String, plugin_name: String) -> TsApi {
+		TsApi {
+			servers: Map::new(),
+			plugin_id: plugin_id,
+			plugin_name,
+			custom_device: RefCell::new(None),
+			audio_receiver: RefCell::new(None),
+			afk_threshold: RefCell::new(None),
+			afk_connections: RefCell::new(HashSet::new()),
+			reconnects: RefCell::new(Map::new()),
+			event_log: RefCell::new(None),
+			timers: RefCell::new(Vec::new()),
+			next_timer_id: Cell::new(0),
+		}
+	}
 
 	/// Load all currently connected server and their data.
 	/// This should normally be executed after `new()`.
@@ -1092,6 +3360,184 @@ impl TsApi {
 		}
 	}
 
+	/// Shorten a message to at most `max_bytes` bytes without cutting a
+	/// character in half.
+	///
+	/// TeamSpeak rejects messages which are too long, the limit for chat
+	/// messages is `MAX_SIZE_TEXTMESSAGE`. Note that BBCode tags count towards
+	/// that limit.
+	///
+	/// ```
+	/// # use ts3plugin::TsApi;
+	/// // `ü` takes two bytes, so it does not fit into three bytes
+	/// assert_eq!(TsApi::truncate_message("Grüße", 3), "Gr");
+	/// assert_eq!(TsApi::truncate_message("Grüße", 4), "Grü");
+	/// assert_eq!(TsApi::truncate_message("Grüße", 100), "Grüße");
+	/// ```
+	pub fn truncate_message(message: &str, max_bytes: usize) -> String {
+		let mut end = std::cmp::min(message.len(), max_bytes);
+		while !message.is_char_boundary(end) {
+			end -= 1;
+		}
+		message[..end].to_string()
+	}
+
+	/// Escape a text, so TeamSpeak does not interpret it as BBCode.
+	///
+	/// Use this for user supplied text, e.g. nicknames, that is embedded
+	/// into a message with BBCode.
+	pub fn escape_bbcode(text: &str) -> String {
+		let mut escaped = String::with_capacity(text.len());
+		for c in text.chars() {
+			if c == '\\' || c == '[' || c == ']' {
+				escaped.push('\\');
+			}
+			escaped.push(c);
+		}
+		escaped
+	}
+
+	/// Escape a text like TeamSpeak escapes strings in its query protocol.
+	///
+	/// Spaces, `|`, `/`, backslashes and control characters are replaced by
+	/// escape sequences, so the text can be embedded as a single value into
+	/// a command, e.g. into a plugin command. Use [`unescape`] to get the
+	/// original text back.
+	///
+	/// ```
+	/// # use ts3plugin::TsApi;
+	/// let escaped = TsApi::escape("a b|c\\d/e");
+	/// assert_eq!(escaped, "a\\sb\\pc\\\\d\\/e");
+	/// assert_eq!(TsApi::unescape(&escaped), "a b|c\\d/e");
+	/// assert_eq!(TsApi::unescape(&TsApi::escape("line\n\ttab")), "line\n\ttab");
+	/// assert_eq!(TsApi::unescape("unknown\\x"), "unknown\\x");
+	/// ```
+	///
+	/// [`unescape`]: #method.unescape
+	pub fn escape(text: &str) -> String {
+		let mut escaped = String::with_capacity(text.len());
+		for c in text.chars() {
+			let e = match c {
+				'\\' => '\\',
+				'/' => '/',
+				' ' => 's',
+				'|' => 'p',
+				'\u{7}' => 'a',
+				'\u{8}' => 'b',
+				'\u{c}' => 'f',
+				'\n' => 'n',
+				'\r' => 'r',
+				'\t' => 't',
+				'\u{b}' => 'v',
+				_ => {
+					escaped.push(c);
+					continue;
+				}
+			};
+			escaped.push('\\');
+			escaped.push(e);
+		}
+		escaped
+	}
+
+	/// Reverse [`escape`].
+	///
+	/// Unknown escape sequences and a backslash at the end are kept as they
+	/// are.
+	///
+	/// [`escape`]: #method.escape
+	pub fn unescape(text: &str) -> String {
+		let mut unescaped = String::with_capacity(text.len());
+		let mut chars = text.chars();
+		while let Some(c) = chars.next() {
+			if c != '\\' {
+				unescaped.push(c);
+				continue;
+			}
+			let e = match chars.next() {
+				Some(e) => e,
+				None => {
+					unescaped.push('\\');
+					break;
+				}
+			};
+			unescaped.push(match e {
+				'\\' => '\\',
+				'/' => '/',
+				's' => ' ',
+				'p' => '|',
+				'a' => '\u{7}',
+				'b' => '\u{8}',
+				'f' => '\u{c}',
+				'n' => '\n',
+				'r' => '\r',
+				't' => '\t',
+				'v' => '\u{b}',
+				_ => {
+					unescaped.push('\\');
+					unescaped.push(e);
+					continue;
+				}
+			});
+		}
+		unescaped
+	}
+
+	/// Show a text in a color, e.g. `TsApi::bbcode_color("Error", "#ff0000")`.
+	///
+	/// The color can be a name like `red` or a hex value like `#ff0000`.
+	/// The text gets escaped, so tags created by these helpers cannot be nested.
+	pub fn bbcode_color(text: &str, color: &str) -> String {
+		let color: String = color.chars().filter(|c| *c != '[' && *c != ']').collect();
+		format!("[color={}]{}[/color]", color, TsApi::escape_bbcode(text))
+	}
+
+	/// Show a text in bold.
+	///
+	/// The text gets escaped, so tags created by these helpers cannot be nested.
+	pub fn bbcode_bold(text: &str) -> String { format!("[b]{}[/b]", TsApi::escape_bbcode(text)) }
+
+	/// Split a message into parts of at most `max_bytes` bytes.
+	///
+	/// Splits only happen at character boundaries, if possible at line breaks.
+	/// If a single character is longer than `max_bytes`, it gets a part of its
+	/// own, so that part is longer.
+	///
+	/// ```
+	/// # use ts3plugin::TsApi;
+	/// assert_eq!(TsApi::split_message("Grüße", 3), vec!["Gr", "ü", "ße"]);
+	/// assert_eq!(TsApi::split_message("ab\ncd", 4), vec!["ab", "cd"]);
+	/// // `€` takes three bytes
+	/// assert_eq!(TsApi::split_message("€€", 2), vec!["€", "€"]);
+	/// ```
+	pub fn split_message(mut message: &str, max_bytes: usize) -> Vec<&str> {
+		let mut parts = Vec::new();
+		while message.len() > max_bytes {
+			let mut end = max_bytes;
+			while !message.is_char_boundary(end) {
+				end -= 1;
+			}
+			if end == 0 {
+				// Always take at least one character to make progress
+				end = message.chars().next().map_or(0, char::len_utf8);
+			}
+			// Prefer to split at a line break and drop it
+			let mut next = end;
+			if let Some(i) = message[..end].rfind('\n') {
+				if i > 0 {
+					end = i;
+					next = i + 1;
+				}
+			}
+			parts.push(&message[..end]);
+			message = &message[next..];
+		}
+		if !message.is_empty() || parts.is_empty() {
+			parts.push(message);
+		}
+		parts
+	}
+
 	/// Please try to use the member method `get_error_message` instead of this static method.
 	pub fn static_get_error_message(error: Error) -> Result<String, Error> {
 		unsafe {
@@ -1144,7 +3590,9 @@ impl TsApi {
 	/// limit.
 	/// The function that is colled takes a pointer to a string buffer that will
 	/// be filled and the max lenght of the buffer.
-	fn get_path<F: Fn(*mut c_char, usize)>(fun: F) -> String {
+	/// Fails with `Error::ParameterInvalidSize` if the path does not fit into
+	/// the largest buffer.
+	fn get_path<F: Fn(*mut c_char, usize)>(fun: F) -> Result<String, Error> {
 		const START_SIZE: usize = 512;
 		const MAX_SIZE: usize = 100_000;
 		let mut size = START_SIZE;
@@ -1155,14 +3603,14 @@ impl TsApi {
 			if buf[size - 3] != 0 {
 				size *= 2;
 				if size > MAX_SIZE {
-					return String::new();
+					return Err(Error::ParameterInvalidSize);
 				}
 			} else {
 				// Be sure that the string is terminated
 				buf[size - 1] = 0;
 				let s = unsafe { CStr::from_ptr(buf.as_ptr() as *const c_char) };
 				let result = s.to_string_lossy();
-				return result.into_owned();
+				return Ok(result.into_owned());
 			}
 		}
 	}
@@ -1214,16 +3662,368 @@ impl TsApi {
 		TsApi::static_log_or_print(message, channel, severity)
 	}
 
+	/// Get a human readable description of an error.
+	///
+	/// Falls back to the name of the error if TeamSpeak does not know it.
+	pub fn get_error_message(&self, error: Error) -> String {
+		TsApi::static_get_error_message(error).unwrap_or_else(|_| format!("{:?}", error))
+	}
+
 	/// Get the server that has the specified id, returns `None` if there is no
 	/// such server.
 	pub fn get_server(&self, server_id: ServerId) -> Option<Server> {
 		self.servers.get(&server_id).map(|s| Server::new(&self, s))
 	}
 
+	/// Get the server that has the specified unique identifier, returns `None`
+	/// if we are not connected to such a server.
+	///
+	/// In contrast to the `ServerId`, the unique identifier stays the same
+	/// when reconnecting to a server.
+	pub fn get_server_by_uid<'a>(&'a self, uid: &str) -> Option<Server<'a>> {
+		self.get_servers().into_iter().find(|s| s.get_uid() == Ok(uid))
+	}
+
+	/// Get the server that has the specified name, returns `None` if we are
+	/// not connected to such a server.
+	///
+	/// If multiple servers have the same name, any of them is returned.
+	pub fn get_server_by_name<'a>(&'a self, name: &str) -> Option<Server<'a>> {
+		self.get_servers().into_iter().find(|s| s.get_name() == Ok(name))
+	}
+
+	/// Get the id of our own connection on a server.
+	///
+	/// This asks TeamSpeak directly, so it works while the connection is
+	/// still being established and [`get_server`] returns `None`, e.g. in
+	/// `Plugin::connect_status_change`. Most other data of the server is
+	/// unavailable until the status changed to `ConnectStatus::Connected`.
+	///
+	/// [`get_server`]: #method.get_server
+	pub fn get_own_connection_id(&self, server_id: ServerId) -> Result<ConnectionId, Error> {
+		ServerData::query_own_connection_id(server_id)
+	}
+
+	/// Format all properties of a server and their values, one per line.
+	///
+	/// Properties that could not be fetched show their error. This is meant
+	/// for debugging and bug reports, the format may change.
+	pub fn dump_server(&self, server: &Server) -> String {
+		let mut dump = format!("{:?}\n", server);
+		for property in server.properties() {
+			dump.push_str(&format!("{:?}\n", property));
+		}
+		dump
+	}
+
+	/// Query all data of a server again from TeamSpeak, e.g. if the cache
+	/// seems to be stale after a reconnect.
+	///
+	/// This needs mutable access to the api, outside of `Plugin::new` it can
+	/// be obtained through [`TsApi::lock_api`].
+	/// Returns `None` if we are not connected to the server.
+	///
+	/// [`TsApi::lock_api`]: #method.lock_api
+	pub fn refresh_server(&mut self, server_id: ServerId) -> Option<ServerRefresh> {
+		let server = self.servers.get_mut(&server_id)?;
+		let old_connections: Map<_, _> = server
+			.visible_connections
+			.iter()
+			.map(|(id, c)| {
+				(*id, (c.talking_since, c.variables_requested.get(), c.variables_received))
+			})
+			.collect();
+		let old_channels: Vec<_> =
+			server.channels.as_ref().map(|cs| cs.keys().cloned().collect()).unwrap_or_default();
+
+		server.update();
+
+		let mut refresh = ServerRefresh::default();
+		for (id, connection) in server.visible_connections.iter_mut() {
+			match old_connections.get(id) {
+				// Keep data that TeamSpeak does not store
+				Some(&(talking_since, variables_requested, variables_received)) => {
+					connection.talking_since = talking_since;
+					connection.variables_requested.set(variables_requested);
+					connection.variables_received = variables_received;
+				}
+				None => refresh.added_connections += 1,
+			}
+		}
+		refresh.removed_connections = old_connections
+			.keys()
+			.filter(|id| !server.visible_connections.contains_key(id))
+			.count();
+		if let Ok(ref cs) = server.channels {
+			refresh.added_channels = cs.keys().filter(|id| !old_channels.contains(id)).count();
+			refresh.removed_channels =
+				old_channels.iter().filter(|id| !cs.contains_key(id)).count();
+		}
+		Some(refresh)
+	}
+
 	pub fn get_permission(&self, _permission_id: PermissionId) -> Option<&Permission> { todo!() }
 
+	/// Register a custom sound device, which can be used to send voice data
+	/// with [`Server::send_voice_samples`].
+	///
+	/// The device captures and plays back with 48 kHz and the given number of
+	/// channels. Only one custom device can be registered at a time.
+	///
+	/// [`Server::send_voice_samples`]: struct.Server.html#method.send_voice_samples
+	pub fn register_custom_device<S1: AsRef<str>, S2: AsRef<str>>(
+		&self, device_id: S1, display_name: S2, channels: i32,
+	) -> Result<(), Error> {
+		if self.custom_device.borrow().is_some() {
+			return Err(Error::SoundDeviceAlreadyRegisterred);
+		}
+		unsafe {
+			let res: Error = transmute((TS3_FUNCTIONS
+				.as_ref()
+				.expect("Functions should be loaded")
+				.register_custom_device)(
+				to_cstring!(device_id.as_ref()).as_ptr(),
+				to_cstring!(display_name.as_ref()).as_ptr(),
+				48000,
+				channels,
+				48000,
+				channels,
+			));
+			match res {
+				Error::Ok => {
+					*self.custom_device.borrow_mut() =
+						Some((device_id.as_ref().to_string(), channels));
+					Ok(())
+				}
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Unregister the custom sound device of this plugin.
+	pub fn unregister_custom_device(&self) -> Result<(), Error> {
+		let device_id = match self.custom_device.borrow_mut().take() {
+			Some((id, _)) => id,
+			None => return Err(Error::SoundUnknownDevice),
+		};
+		unsafe {
+			let res: Error =
+				transmute((TS3_FUNCTIONS
+					.as_ref()
+					.expect("Functions should be loaded")
+					.unregister_custom_device)(to_cstring!(device_id).as_ptr()));
+			match res {
+				Error::Ok => Ok(()),
+				_ => Err(res),
+			}
+		}
+	}
+
+	/// Buffer the voice data of the audio callbacks instead of passing it to
+	/// the plugin.
+	///
+	/// The audio callbacks are called from another thread and every call has
+	/// to wait until the plugin finished handling other events. While the
+	/// buffer is enabled, [`Plugin::playback_voice_data`] and
+	/// [`Plugin::captured_voice_data`] are not called anymore, instead a copy of
+	/// the audio is pushed into a buffer without blocking the audio thread.
+	/// The buffered packets can be fetched with [`drain_audio`].
+	///
+	/// The buffer holds up to `capacity` packets, if it is full, new packets
+	/// are dropped. TeamSpeak delivers a packet every 20 ms per connection.
+	/// Enabling the buffer again discards all buffered packets.
+	///
+	/// [`Plugin::playback_voice_data`]: plugin/trait.Plugin.html#method.playback_voice_data
+	/// [`Plugin::captured_voice_data`]: plugin/trait.Plugin.html#method.captured_voice_data
+	/// [`drain_audio`]: #method.drain_audio
+	pub fn enable_audio_buffer(&self, capacity: usize) {
+		let (sender, receiver) = mpsc::sync_channel(capacity);
+		*ts3interface::AUDIO_BUFFER.write().unwrap() = Some(sender);
+		*self.audio_receiver.borrow_mut() = Some(receiver);
+	}
+
+	/// Stop buffering voice data and pass it to the plugin callbacks again.
+	pub fn disable_audio_buffer(&self) {
+		*ts3interface::AUDIO_BUFFER.write().unwrap() = None;
+		*self.audio_receiver.borrow_mut() = None;
+	}
+
+	/// Take all packets out of the audio buffer.
+	///
+	/// Returns an empty list if the buffer is not enabled.
+	pub fn drain_audio(&self) -> Vec<AudioPacket> {
+		self.audio_receiver.borrow().as_ref().map_or(Vec::new(), |r| r.try_iter().collect())
+	}
+
+	/// Detect connections that are away from keyboard.
+	///
+	/// A connection is away when it was idle for at least `threshold`, then
+	/// [`Plugin::client_went_afk`] is called and [`Plugin::client_returned`]
+	/// when it is active again. The connection info of all visible
+	/// connections is requested every ten seconds from another thread and
+	/// the idle times are checked when it arrives, before
+	/// [`Plugin::connection_info`] is called.
+	///
+	/// [`Plugin::client_went_afk`]: plugin/trait.Plugin.html#method.client_went_afk
+	/// [`Plugin::client_returned`]: plugin/trait.Plugin.html#method.client_returned
+	/// [`Plugin::connection_info`]: plugin/trait.Plugin.html#method.connection_info
+	pub fn set_afk_threshold(&self, threshold: Duration) {
+		*self.afk_threshold.borrow_mut() = Some(threshold);
+		ts3interface::start_afk_timer();
+	}
+
+	/// Stop detecting connections that are away from keyboard.
+	pub fn disable_afk_detection(&self) {
+		*self.afk_threshold.borrow_mut() = None;
+		self.afk_connections.borrow_mut().clear();
+	}
+
+	/// Record the idle time of a connection for the afk detection.
+	///
+	/// Returns `None` if the detection is disabled or the connection is
+	/// still away or still active.
+	fn update_afk(
+		&self, server_id: ServerId, connection_id: ConnectionId, idle_time: Duration,
+	) -> Option<AfkChange> {
+		let threshold = (*self.afk_threshold.borrow())?;
+		let key = (server_id, connection_id);
+		let mut afk_connections = self.afk_connections.borrow_mut();
+		if idle_time >= threshold {
+			if afk_connections.insert(key) {
+				return Some(AfkChange::WentAfk);
+			}
+		} else if afk_connections.remove(&key) {
+			return Some(AfkChange::Returned);
+		}
+		None
+	}
+
+	/// Start recording events, at most the last `capacity` events are kept.
+	///
+	/// The log records connections that join, leave or switch channels and
+	/// received text messages. If the log is already enabled, only the
+	/// capacity is changed.
+	pub fn enable_event_log(&self, capacity: usize) {
+		let mut log = self.event_log.borrow_mut();
+		let log = log.get_or_insert_with(|| EventLog {
+			capacity,
+			events: VecDeque::with_capacity(capacity),
+		});
+		log.capacity = capacity;
+		while log.events.len() > capacity {
+			log.events.pop_front();
+		}
+	}
+
+	/// Stop recording events and forget the recorded events.
+	pub fn disable_event_log(&self) { *self.event_log.borrow_mut() = None; }
+
+	/// Get the recorded events with the time when they happened, the oldest
+	/// event comes first.
+	///
+	/// The list is empty if the event log is not enabled.
+	pub fn recent_events(&self) -> Vec<(Timestamp, Event)> {
+		self.event_log
+			.borrow()
+			.as_ref()
+			.map_or(Vec::new(), |log| log.events.iter().cloned().collect())
+	}
+
+	/// Record an event if the event log is enabled.
+	pub(crate) fn log_event(&self, event: Event) {
+		if let Some(ref mut log) = *self.event_log.borrow_mut() {
+			if log.events.len() >= log.capacity {
+				log.events.pop_front();
+			}
+			if log.capacity > 0 {
+				log.events.push_back((time::now(), event));
+			}
+		}
+	}
+
+	/// Call `f` repeatedly, the first time after `interval`.
+	///
+	/// The closure runs on a thread of this library, which holds the lock of
+	/// the api and the plugin meanwhile, like for the callbacks of the
+	/// [`Plugin`]. So [`lock_api`] and [`lock_plugin`] must not be called in
+	/// the closure, share data with the plugin through the captured values
+	/// instead. Timers are dropped when the plugin is unloaded.
+	///
+	/// Fails with `Error::ParameterInvalid` if `interval` is not positive.
+	///
+	/// [`Plugin`]: plugin/trait.Plugin.html
+	/// [`lock_api`]: #method.lock_api
+	/// [`lock_plugin`]: #method.lock_plugin
+	pub fn schedule<F: FnMut(&mut TsApi) + Send + 'static>(
+		&self, interval: Duration, f: F,
+	) -> Result<TimerId, Error> {
+		let interval = match time::duration_to_std(interval) {
+			Some(interval) if interval > std::time::Duration::from_secs(0) => interval,
+			_ => return Err(Error::ParameterInvalid),
+		};
+		let id = TimerId(self.next_timer_id.get());
+		self.next_timer_id.set(id.0 + 1);
+		self.timers.borrow_mut().push(Timer {
+			id,
+			interval,
+			next: std::time::Instant::now() + interval,
+			f: Some(Box::new(f)),
+		});
+		ts3interface::start_timer_thread();
+		Ok(id)
+	}
+
+	/// Stop a timer that was registered with [`schedule`].
+	///
+	/// Returns `false` if the timer does not exist anymore.
+	///
+	/// [`schedule`]: #method.schedule
+	pub fn cancel_timer(&self, id: TimerId) -> bool {
+		let mut timers = self.timers.borrow_mut();
+		let len = timers.len();
+		timers.retain(|t| t.id != id);
+		timers.len() != len
+	}
+
+	/// Check if there are registered timers.
+	pub(crate) fn has_timers(&self) -> bool { !self.timers.borrow().is_empty() }
+
+	/// Call the timers that are due.
+	///
+	/// Returns how long to wait until the next timer is due or `None` if no
+	/// timers are left.
+	pub(crate) fn run_timers(&mut self) -> Option<std::time::Duration> {
+		// Drop timers that panicked
+		self.timers.borrow_mut().retain(|t| t.f.is_some());
+		let now = std::time::Instant::now();
+		let due: Vec<_> =
+			self.timers.borrow().iter().filter(|t| t.next <= now).map(|t| t.id).collect();
+		for id in due {
+			// The closure may schedule or cancel timers, so it is taken out
+			// of the list while it runs.
+			let f = self.timers.borrow_mut().iter_mut().find(|t| t.id == id).and_then(|t| {
+				t.next = now + t.interval;
+				t.f.take()
+			});
+			if let Some(mut f) = f {
+				f(self);
+				if let Some(t) = self.timers.borrow_mut().iter_mut().find(|t| t.id == id) {
+					t.f = Some(f);
+				}
+			}
+		}
+		let now = std::time::Instant::now();
+		self.timers.borrow().iter().map(|t| t.next.saturating_duration_since(now)).min()
+	}
+
 	/// Print a message to the currently selected tab. This is only
 	/// visible in the window of this client and will not be sent to the server.
+	///
+	/// The message is rendered as BBCode, see [`bbcode_color`] and
+	/// [`escape_bbcode`].
+	///
+	/// [`bbcode_color`]: #method.bbcode_color
+	/// [`escape_bbcode`]: #method.escape_bbcode
 	pub fn print_message<S: AsRef<str>>(&self, message: S) {
 		unsafe {
 			let text = to_cstring!(message.as_ref());
@@ -1235,7 +4035,7 @@ impl TsApi {
 	}
 
 	/// Get the application path of the TeamSpeak executable.
-	pub fn get_app_path(&self) -> String {
+	pub fn get_app_path(&self) -> Result<String, Error> {
 		unsafe {
 			TsApi::get_path(|p, l| {
 				(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").get_app_path)(p, l)
@@ -1244,7 +4044,7 @@ impl TsApi {
 	}
 
 	/// Get the resource path of TeamSpeak.
-	pub fn get_resources_path(&self) -> String {
+	pub fn get_resources_path(&self) -> Result<String, Error> {
 		unsafe {
 			TsApi::get_path(|p, l| {
 				(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").get_resources_path)(
@@ -1256,7 +4056,7 @@ impl TsApi {
 
 	/// Get the path, where configuration files are stored.
 	/// This is e.g. `~/.ts3client` on linux or `%AppData%/TS3Client` on Windows.
-	pub fn get_config_path(&self) -> String {
+	pub fn get_config_path(&self) -> Result<String, Error> {
 		unsafe {
 			TsApi::get_path(|p, l| {
 				(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").get_config_path)(p, l)
@@ -1265,7 +4065,13 @@ impl TsApi {
 	}
 
 	/// Get the path where TeamSpeak plugins are stored.
-	pub fn get_plugin_path(&self) -> String {
+	///
+	/// Fails with `Error::ParameterInvalid` if TeamSpeak did not register a
+	/// plugin id yet.
+	pub fn get_plugin_path(&self) -> Result<String, Error> {
+		if self.plugin_id.is_empty() {
+			return Err(Error::ParameterInvalid);
+		}
 		unsafe {
 			TsApi::get_path(|p, l| {
 				(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").get_plugin_path)(
@@ -1276,4 +4082,35 @@ impl TsApi {
 			})
 		}
 	}
+
+	/// Get the directory where this plugin should store its configuration.
+	///
+	/// This is a directory named like the plugin inside the configuration
+	/// path of TeamSpeak, it gets created if it does not exist.
+	pub fn plugin_config_dir(&self) -> io::Result<PathBuf> {
+		let name = self.plugin_name.replace(&['/', '\\'] as &[char], "_");
+		let config_path =
+			self.get_config_path().map_err(|e| io::Error::other(format!("{:?}", e)))?;
+		let dir = PathBuf::from(config_path).join(name);
+		fs::create_dir_all(&dir)?;
+		Ok(dir)
+	}
+
+	/// Read a file from the configuration directory of this plugin.
+	///
+	/// See [`plugin_config_dir`].
+	///
+	/// [`plugin_config_dir`]: #method.plugin_config_dir
+	pub fn read_config(&self, file_name: &str) -> io::Result<String> {
+		fs::read_to_string(self.plugin_config_dir()?.join(file_name))
+	}
+
+	/// Write a file into the configuration directory of this plugin.
+	///
+	/// See [`plugin_config_dir`].
+	///
+	/// [`plugin_config_dir`]: #method.plugin_config_dir
+	pub fn write_config<C: AsRef<[u8]>>(&self, file_name: &str, content: C) -> io::Result<()> {
+		fs::write(self.plugin_config_dir()?.join(file_name), content)
+	}
 }
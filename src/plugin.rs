@@ -14,6 +14,73 @@ pub enum InitError {
 	FailureNoMessage,
 }
 
+/// A guess why a plugin is shut down.
+///
+/// TeamSpeak calls the shutdown of a plugin in the same way when the user
+/// disables it and when the client exits, so the reason cannot be known. It
+/// is only guessed from whether there are still connections to servers.
+/// Do not rely on it, e.g. state that must survive should be saved for
+/// every reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+	/// There are still connections to servers, so the plugin was probably
+	/// disabled by the user.
+	LikelyUserDisabled,
+	/// There are no connections to servers. TeamSpeak may be exiting or the
+	/// plugin was disabled while not connected.
+	LikelyClientExit,
+}
+
+/// Why the connection to a server was closed.
+///
+/// This is derived from the error of the connection status change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+	/// The connection was closed without an error, e.g. because we
+	/// disconnected or were kicked.
+	Normal,
+	/// We are banned from the server.
+	Banned,
+	/// The connection timed out.
+	ConnectionLost,
+	/// The server was shut down.
+	ServerShutdown,
+	/// The server has no free slot.
+	ServerFull,
+	/// The server password is wrong.
+	InvalidPassword,
+	/// Another error.
+	Other(::Error),
+}
+
+impl DisconnectReason {
+	/// Map the error of a connection status change to a reason.
+	pub fn from_error(error: ::Error) -> DisconnectReason {
+		match error {
+			::Error::Ok => DisconnectReason::Normal,
+			::Error::ConnectFailedBanned => DisconnectReason::Banned,
+			::Error::ConnectionLost => DisconnectReason::ConnectionLost,
+			::Error::ServerShutdown | ::Error::ServerIsShuttingDown => {
+				DisconnectReason::ServerShutdown
+			}
+			::Error::ServerMaxclientsReached => DisconnectReason::ServerFull,
+			::Error::ServerInvalidPassword => DisconnectReason::InvalidPassword,
+			_ => DisconnectReason::Other(error),
+		}
+	}
+
+	/// If connecting again later could succeed, e.g. after a timeout but not
+	/// after a ban.
+	pub fn is_temporary(&self) -> bool {
+		matches!(
+			*self,
+			DisconnectReason::ConnectionLost
+				| DisconnectReason::ServerShutdown
+				| DisconnectReason::ServerFull
+		)
+	}
+}
+
 /// This trait that has to be implemented by a plugin. To enhance a library to a
 /// working TeamSpeak plugin you have to call the macro [`create_plugin!`]
 /// afterwards.
@@ -80,9 +147,15 @@ pub trait Plugin: 'static + Send {
 	/// If `status = ConnectStatus::Connecting`, the connection is not yet
 	/// registered in the [`TsApi`].
 	///
+	/// `error_message` contains the description of `error` if it is not
+	/// `Error::Ok`.
+	/// `reason` tells why we were disconnected if the status is
+	/// `ConnectStatus::Disconnected`, otherwise it is `None`.
+	///
 	/// [`TsApi`]: ../struct.TsApi.html
 	fn connect_status_change(
 		&mut self, api: &::TsApi, server: &::Server, status: ::ConnectStatus, error: ::Error,
+		error_message: Option<String>, reason: Option<DisconnectReason>,
 	) {
 	}
 
@@ -107,25 +180,119 @@ pub trait Plugin: 'static + Send {
 
 	fn connection_info(&mut self, api: &::TsApi, server: &::Server, connection: &::Connection) {}
 
+	/// Called for every connection that belongs to a unique identifier after
+	/// the ids were requested with [`Server::request_client_ids`].
+	///
+	/// The connection does not have to be visible.
+	/// [`Plugin::client_ids_finished`] is called after the last connection.
+	///
+	/// [`Server::request_client_ids`]: ../struct.Server.html#method.request_client_ids
+	/// [`Plugin::client_ids_finished`]: #method.client_ids_finished
+	fn client_ids(
+		&mut self, api: &::TsApi, server: &::Server, uid: String, connection_id: ::ConnectionId,
+		name: String,
+	) {
+	}
+
+	/// Called when all connections for a [`Server::request_client_ids`] call
+	/// were reported.
+	///
+	/// [`Server::request_client_ids`]: ../struct.Server.html#method.request_client_ids
+	fn client_ids_finished(&mut self, api: &::TsApi, server: &::Server) {}
+
+	/// Called when the permissions of a client in a channel, that were
+	/// requested with [`Server::request_channel_client_permissions`],
+	/// arrived.
+	///
+	/// [`Server::request_channel_client_permissions`]: ../struct.Server.html#method.request_channel_client_permissions
+	fn channel_client_permissions(
+		&mut self, api: &::TsApi, server: &::Server, channel: &::Channel, database_id: u64,
+		permissions: &[::ChannelClientPermission],
+	) {
+	}
+
+	/// Called when a file list that was requested with
+	/// [`Server::request_file_list`] arrived.
+	///
+	/// `entries` contains all files and directories in `path`.
+	///
+	/// [`Server::request_file_list`]: ../struct.Server.html#method.request_file_list
+	fn file_list(
+		&mut self, api: &::TsApi, server: &::Server, channel: &::Channel, path: &str,
+		entries: &[::FileEntry],
+	) {
+	}
+
+	/// Called when the list of server groups was received, e.g. after it was
+	/// requested with [`Server::request_server_group_list`].
+	///
+	/// `groups` contains all server groups of the server, sorted by their id.
+	/// They are also cached and available with [`Server::get_server_group`].
+	///
+	/// [`Server::request_server_group_list`]: ../struct.Server.html#method.request_server_group_list
+	/// [`Server::get_server_group`]: ../struct.Server.html#method.get_server_group
+	fn server_group_list_changed(
+		&mut self, api: &::TsApi, server: &::Server, groups: &[::ServerGroup],
+	) {
+	}
+
+	/// Called for every server group of a client after the groups were
+	/// requested with [`Server::request_server_groups_by_client`].
+	///
+	/// `groups` contains all groups that were received since the request.
+	/// `connection` is `None` if the client is not visible.
+	///
+	/// [`Server::request_server_groups_by_client`]: ../struct.Server.html#method.request_server_groups_by_client
+	fn server_groups_by_client(
+		&mut self, api: &::TsApi, server: &::Server, connection: Option<&::Connection>,
+		database_id: u64, groups: &[::ServerGroup],
+	) {
+	}
+
 	fn connection_properties_changed(
 		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection,
 		old_connection: &::Connection, changes: ::ConnectionChanges, invoker: &::Invoker,
 	) {
 	}
 
+	/// Called if the properties of our own connection changed, e.g. when we
+	/// set ourselves away.
+	///
+	/// This is called after [`Plugin::connection_properties_changed`].
+	///
+	/// [`Plugin::connection_properties_changed`]: #method.connection_properties_changed
+	fn own_properties_changed(
+		&mut self, api: &::TsApi, server: &::Server, old_connection: &::Connection,
+		connection: &::Connection, changes: ::ConnectionChanges,
+	) {
+	}
+
+	/// Called if a connection started or stopped recording.
+	///
+	/// This is called after [`Plugin::connection_properties_changed`].
+	///
+	/// [`Plugin::connection_properties_changed`]: #method.connection_properties_changed
+	fn recording_changed(
+		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection, recording: bool,
+	) {
+	}
+
 	/// If the plugin was informed about a new connection. If appeared is true, the connection
 	/// was previously not known to the plugin, if appeared is false, the connection left
 	/// the view of connection.
+	/// The invoker is `None` if TeamSpeak does not tell who caused the
+	/// announcement, e.g. if the connection was not moved by someone else.
 	fn connection_announced(
 		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection, appeared: bool,
+		invoker: Option<&::Invoker>,
 	) {
 	}
 
 	/// Called, if a connection connects to the server. This is also called for our own
-	/// connection.
+	/// connection, then `is_self` is `true`.
 	fn connection_changed(
 		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection, connected: bool,
-		message: String,
+		is_self: bool, message: String,
 	) {
 	}
 
@@ -144,11 +311,44 @@ pub trait Plugin: 'static + Send {
 	) {
 	}
 
+	/// Called if our own connection switched the channel.
+	///
+	/// This is called after [`Plugin::connection_move`] or
+	/// [`Plugin::connection_moved`]. The invoker is `None` if we switched the
+	/// channel ourselves.
+	///
+	/// [`Plugin::connection_move`]: #method.connection_move
+	/// [`Plugin::connection_moved`]: #method.connection_moved
+	fn own_connection_moved(
+		&mut self, api: &::TsApi, server: &::Server, old_channel: &::Channel,
+		new_channel: &::Channel, invoker: Option<&::Invoker>,
+	) {
+	}
+
+	/// Called if a connection was idle for longer than the threshold that was
+	/// set with [`TsApi::set_afk_threshold`].
+	///
+	/// [`TsApi::set_afk_threshold`]: ../struct.TsApi.html#method.set_afk_threshold
+	fn client_went_afk(
+		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection,
+		idle_time: ::Duration,
+	) {
+	}
+
+	/// Called if a connection that was away from keyboard is active again.
+	///
+	/// See [`Plugin::client_went_afk`].
+	///
+	/// [`Plugin::client_went_afk`]: #method.client_went_afk
+	fn client_returned(&mut self, api: &::TsApi, server: &::Server, connection: &::Connection) {}
+
 	/// Called when a connection times out.
 	fn connection_timeout(&mut self, api: &::TsApi, server: &::Server, connection: &::Connection) {}
 
 	/// Called if a channel is announced to the client.
 	/// This will be called for each channel when connecting to a server.
+	/// TeamSpeak does not tell who caused the announcement, so there is no
+	/// invoker.
 	fn channel_announced(&mut self, api: &::TsApi, server: &::Server, channel: &::Channel) {}
 
 	/// Called if the channel description was changed.
@@ -209,6 +409,26 @@ pub trait Plugin: 'static + Send {
 		false
 	}
 
+	/// A message was received, this is called before [`Plugin::message`] and
+	/// allows to change the message, e.g. to censor it. [`Plugin::message`]
+	/// gets the changed message.
+	///
+	/// TeamSpeak does not allow to change a received message. If the message
+	/// gets changed, the original message is hidden and the changed message is
+	/// printed into the chat instead. This is only visible for our own client,
+	/// all other clients still see the original message.
+	/// Private messages are printed into the current tab.
+	///
+	/// Return `true` if the client should ignore the message.
+	///
+	/// [`Plugin::message`]: #method.message
+	fn message_mut(
+		&mut self, api: &::TsApi, server: &::Server, invoker: &::Invoker,
+		target: ::MessageReceiver, message: &mut String, ignored: bool,
+	) -> bool {
+		false
+	}
+
 	/// A user poked us. `ignored` describes, if the friend and fool system
 	/// of TeamSpeak ignored the message.
 	/// Return `false` if the TeamSpeak client should handle the poke normally or
@@ -247,16 +467,34 @@ pub trait Plugin: 'static + Send {
 	) {
 	}
 
+	/// Called if the name that is shown for a connection changed. This is not
+	/// necessarily the nickname, e.g. contacts can have a custom display name.
+	/// The old display name is available from the connection.
+	/// It will be updated after this functions returned.
+	fn client_display_name_changed(
+		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection,
+		display_name: String,
+	) {
+	}
+
 	/// Called if the avatar of a client is updated.
 	/// This also happens when the avatar is discovered for the first time.
 	/// The avatar information are only fetched if requested, e.g. if the
 	/// user clicks on a connection.
+	///
+	/// `path` is the path of the downloaded avatar file.
 	fn avatar_changed(
-		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection,
-		path: Option<String>,
+		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection, path: String,
 	) {
 	}
 
+	/// Called if a client removed its avatar.
+	///
+	/// TeamSpeak signals this with an update without a path. Clients that
+	/// never had an avatar do not trigger this callback, so a cached avatar
+	/// for this connection can be removed.
+	fn avatar_removed(&mut self, api: &::TsApi, server: &::Server, connection: &::Connection) {}
+
 	/// Called if a channel group is assigned to a connection.
 	fn connection_channel_group_changed(
 		&mut self, api: &::TsApi, server: &::Server, connection: &::Connection,
@@ -362,6 +600,9 @@ pub trait Plugin: 'static + Send {
 	///
 	/// Messages can be sent with [`Server::send_plugin_message`].
 	/// The message is called `PluginCommand` by TeamSpeak.
+	/// TeamSpeak does not tell to which connections the message was
+	/// addressed, so a plugin that needs to know this has to put the targets
+	/// into the message itself.
 	///
 	/// [`Server::send_plugin_message`]: ../struct.Server.html#method.send_plugin_message
 	fn plugin_message(
@@ -385,7 +626,114 @@ pub trait Plugin: 'static + Send {
 
 	/// Called if the plugin is getting disabled (either by the user or if
 	/// TeamSpeak is exiting).
-	fn shutdown(&mut self, api: &::TsApi) {}
+	///
+	/// The reason is only a guess, see [`ShutdownReason`].
+	///
+	/// [`ShutdownReason`]: enum.ShutdownReason.html
+	fn shutdown(&mut self, api: &::TsApi, reason: ShutdownReason) {}
+}
+
+/// Overwrite the configuration of a plugin at runtime.
+///
+/// Every value that is not set is taken from the configuration methods of
+/// the [`Plugin`] trait. The builder is created when TeamSpeak asks for the
+/// first value and is passed to [`create_plugin!`].
+///
+/// # Examples
+///
+/// ```ignore
+/// fn builder() -> PluginBuilder {
+///     let builder = PluginBuilder::new().author("Me");
+///     match std::env::var("MY_PLUGIN_NAME") {
+///         Ok(name) => builder.name(name),
+///         Err(_) => builder,
+///     }
+/// }
+///
+/// create_plugin!(MyTsPlugin, builder);
+/// ```
+///
+/// [`Plugin`]: trait.Plugin.html
+/// [`create_plugin!`]: ../macro.create_plugin.html
+#[derive(Debug, Clone, Default)]
+pub struct PluginBuilder {
+	name: Option<String>,
+	version: Option<String>,
+	author: Option<String>,
+	description: Option<String>,
+	command: Option<Option<String>>,
+	configurable: Option<::ConfigureOffer>,
+	autoload: Option<bool>,
+}
+
+impl PluginBuilder {
+	pub fn new() -> PluginBuilder { PluginBuilder::default() }
+
+	/// The name of the plugin as displayed in TeamSpeak.
+	pub fn name<S: Into<String>>(mut self, name: S) -> PluginBuilder {
+		self.name = Some(name.into());
+		self
+	}
+
+	/// The version of the plugin as displayed in TeamSpeak.
+	pub fn version<S: Into<String>>(mut self, version: S) -> PluginBuilder {
+		self.version = Some(version.into());
+		self
+	}
+
+	/// The author of the plugin as displayed in TeamSpeak.
+	pub fn author<S: Into<String>>(mut self, author: S) -> PluginBuilder {
+		self.author = Some(author.into());
+		self
+	}
+
+	/// The description of the plugin as displayed in TeamSpeak.
+	pub fn description<S: Into<String>>(mut self, description: S) -> PluginBuilder {
+		self.description = Some(description.into());
+		self
+	}
+
+	/// The command prefix that can be used by users in the chat, `None`
+	/// disables commands.
+	pub fn command(mut self, command: Option<String>) -> PluginBuilder {
+		self.command = Some(command);
+		self
+	}
+
+	/// If the plugin offers the possibility to be configured.
+	pub fn configurable(mut self, configurable: ::ConfigureOffer) -> PluginBuilder {
+		self.configurable = Some(configurable);
+		self
+	}
+
+	/// If the plugin should be loaded by default or only if activated manually.
+	pub fn autoload(mut self, autoload: bool) -> PluginBuilder {
+		self.autoload = Some(autoload);
+		self
+	}
+
+	/// The name that was set, `None` if the default is used.
+	pub fn get_name(&self) -> Option<&str> { self.name.as_deref() }
+
+	/// The version that was set, `None` if the default is used.
+	pub fn get_version(&self) -> Option<&str> { self.version.as_deref() }
+
+	/// The author that was set, `None` if the default is used.
+	pub fn get_author(&self) -> Option<&str> { self.author.as_deref() }
+
+	/// The description that was set, `None` if the default is used.
+	pub fn get_description(&self) -> Option<&str> { self.description.as_deref() }
+
+	/// The command that was set, `None` if the default is used.
+	pub fn get_command(&self) -> Option<Option<&str>> {
+		self.command.as_ref().map(|c| c.as_deref())
+	}
+
+	/// The configuration offer that was set, `None` if the default is used.
+	pub fn get_configurable(&self) -> Option<::ConfigureOffer> { self.configurable }
+
+	/// If the plugin is loaded automatically, `None` if the default is used.
+	pub fn get_autoload(&self) -> Option<bool> { self.autoload }
 }
 
 /// Save the `CString`s that are returned from the TeamSpeak API.
@@ -397,6 +745,14 @@ pub struct CreatePluginData {
 	pub author: Option<::std::ffi::CString>,
 	pub description: Option<::std::ffi::CString>,
 	pub command: Option<Option<::std::ffi::CString>>,
+	pub builder: Option<PluginBuilder>,
+}
+
+impl CreatePluginData {
+	/// Get the builder of the plugin and create it on the first call.
+	pub fn get_builder(&mut self, create: fn() -> PluginBuilder) -> &PluginBuilder {
+		self.builder.get_or_insert_with(create)
+	}
 }
 
 lazy_static! {
@@ -408,6 +764,7 @@ lazy_static! {
 			author: None,
 			description: None,
 			command: None,
+			builder: None,
 		});
 }
 
@@ -417,6 +774,9 @@ lazy_static! {
 /// function interface that is used by TeamSpeak. The argument is the struct
 /// which implements the [`Plugin`] trait.
 ///
+/// Optionally, a function that returns a [`PluginBuilder`] can be passed
+/// as second argument to overwrite the configuration at runtime.
+///
 /// # Examples
 ///
 /// ```ignore
@@ -424,18 +784,26 @@ lazy_static! {
 /// ```
 ///
 /// [`Plugin`]: plugin/trait.Plugin.html
+/// [`PluginBuilder`]: plugin/struct.PluginBuilder.html
 #[macro_export]
 macro_rules! create_plugin {
 	($typename: ident) => {
+		$crate::create_plugin!($typename, $crate::plugin::PluginBuilder::new);
+	};
+	($typename: ident, $builder: path) => {
 		/// Initialise the plugin and return the error status.
 		#[no_mangle]
 		#[doc(hidden)]
 		pub unsafe extern "C" fn ts3plugin_init() -> std::os::raw::c_int {
-			match $crate::ts3interface::private_init::<$typename>() {
-				Ok(_) => 0,
-				Err($crate::InitError::Failure) => 1,
-				Err($crate::InitError::FailureNoMessage) => -2,
-			}
+			$crate::ts3interface::catch_panic("ts3plugin_init", 1, || {
+				let name =
+					::std::ffi::CStr::from_ptr(ts3plugin_name()).to_string_lossy().into_owned();
+				match $crate::ts3interface::private_init::<$typename>(name) {
+					Ok(_) => 0,
+					Err($crate::InitError::Failure) => 1,
+					Err($crate::InitError::FailureNoMessage) => -2,
+				}
+			})
 		}
 
 		/// Unique name identifying this plugin.
@@ -446,7 +814,11 @@ macro_rules! create_plugin {
 		pub extern "C" fn ts3plugin_name() -> *const std::os::raw::c_char {
 			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
 			if data.name.is_none() {
-				let s = $typename::name();
+				let s = data
+					.get_builder($builder)
+					.get_name()
+					.map(|s| s.to_string())
+					.unwrap_or_else($typename::name);
 				if s == "MAGIC\0" {
 					let s = ::std::ffi::CString::new(env!("CARGO_PKG_NAME"))
 						.expect("Crate name contains nul character");
@@ -467,7 +839,11 @@ macro_rules! create_plugin {
 		pub extern "C" fn ts3plugin_version() -> *const std::os::raw::c_char {
 			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
 			if data.version.is_none() {
-				let s = $typename::version();
+				let s = data
+					.get_builder($builder)
+					.get_version()
+					.map(|s| s.to_string())
+					.unwrap_or_else($typename::version);
 				if s == "MAGIC\0" {
 					let s = ::std::ffi::CString::new(env!("CARGO_PKG_VERSION"))
 						.expect("Crate version contains nul character");
@@ -488,7 +864,11 @@ macro_rules! create_plugin {
 		pub extern "C" fn ts3plugin_author() -> *const std::os::raw::c_char {
 			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
 			if data.author.is_none() {
-				let s = $typename::author();
+				let s = data
+					.get_builder($builder)
+					.get_author()
+					.map(|s| s.to_string())
+					.unwrap_or_else($typename::author);
 				if s == "MAGIC\0" {
 					let s = ::std::ffi::CString::new(env!("CARGO_PKG_AUTHORS"))
 						.expect("Crate author contains nul character");
@@ -509,7 +889,11 @@ macro_rules! create_plugin {
 		pub extern "C" fn ts3plugin_description() -> *const std::os::raw::c_char {
 			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
 			if data.description.is_none() {
-				let s = $typename::description();
+				let s = data
+					.get_builder($builder)
+					.get_description()
+					.map(|s| s.to_string())
+					.unwrap_or_else($typename::description);
 				if s == "MAGIC\0" {
 					let s = ::std::ffi::CString::new(env!("CARGO_PKG_DESCRIPTION"))
 						.expect("Crate description contains nul character");
@@ -531,7 +915,12 @@ macro_rules! create_plugin {
 		pub extern "C" fn ts3plugin_commandKeyword() -> *const std::os::raw::c_char {
 			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
 			if data.command.is_none() {
-				data.command = Some(if let Some(s) = $typename::command() {
+				let command = data
+					.get_builder($builder)
+					.get_command()
+					.map(|c| c.map(|s| s.to_string()))
+					.unwrap_or_else($typename::command);
+				data.command = Some(if let Some(s) = command {
 					let s = ::std::ffi::CString::new(s).expect("String contains nul character");
 					Some(s)
 				} else {
@@ -551,7 +940,9 @@ macro_rules! create_plugin {
 		#[no_mangle]
 		#[doc(hidden)]
 		pub extern "C" fn ts3plugin_offersConfigure() -> std::os::raw::c_int {
-			$typename::configurable() as std::os::raw::c_int
+			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
+			data.get_builder($builder).get_configurable().unwrap_or_else($typename::configurable)
+				as std::os::raw::c_int
 		}
 
 		/// If the plugin should be loaded automatically.
@@ -560,7 +951,12 @@ macro_rules! create_plugin {
 		#[no_mangle]
 		#[doc(hidden)]
 		pub extern "C" fn ts3plugin_requestAutoload() -> std::os::raw::c_int {
-			if $typename::autoload() { 1 } else { 0 }
+			let mut data = CREATE_PLUGIN_DATA.lock().unwrap();
+			if data.get_builder($builder).get_autoload().unwrap_or_else($typename::autoload) {
+				1
+			} else {
+				0
+			}
 		}
 	};
 }
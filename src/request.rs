@@ -0,0 +1,169 @@
+//! Wait for the answer of the server to a request.
+//!
+//! TeamSpeak answers requests that carry a return code with a server error
+//! event, which contains `Error::Ok` if the request succeeded. The requests
+//! that wait for an answer are kept here and the matching [`Request`] future
+//! gets resolved when the answer arrives.
+//!
+//! Answers to these requests are not passed to [`Plugin::server_error`].
+//! The return codes of this library are marked, see [`ReturnCode`], so
+//! answers to return codes that a plugin created itself still arrive there.
+//!
+//! This module is only available with the `async` feature.
+//!
+//! [`Request`]: struct.Request.html
+//! [`ReturnCode`]: struct.ReturnCode.html
+//! [`Plugin::server_error`]: ../plugin/trait.Plugin.html#method.server_error
+
+use std::collections::HashMap as Map;
+use std::ffi::{CStr, CString};
+use std::future::Future;
+use std::os::raw::c_char;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use {Error, ServerId, TS3_FUNCTIONS};
+
+lazy_static! {
+	/// The requests that wait for an answer, by their return code.
+	static ref PENDING: Mutex<Map<ReturnCode, Sender>> = Mutex::new(Map::new());
+}
+
+/// Appended to the return codes of this library, so they cannot be
+/// confused with return codes that a plugin created itself.
+const RETURN_CODE_MARKER: &str = ":rust-ts3plugin";
+
+/// A return code that was created by this library for a request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReturnCode(String);
+
+impl ReturnCode {
+	/// Let TeamSpeak create a new return code for this plugin.
+	fn create(plugin_id: &str) -> ReturnCode {
+		let code = unsafe {
+			let mut buffer = [0 as c_char; 128];
+			(TS3_FUNCTIONS.as_ref().expect("Functions should be loaded").create_return_code)(
+				to_cstring!(plugin_id).as_ptr(),
+				buffer.as_mut_ptr(),
+				buffer.len(),
+			);
+			to_string!(buffer.as_ptr())
+		};
+		// The code from TeamSpeak stays at the start, TeamSpeak uses it to
+		// pass the answer to this plugin.
+		ReturnCode(code + RETURN_CODE_MARKER)
+	}
+
+	/// Parse a return code that was received from the server.
+	///
+	/// Returns `None` if the code was not created by this library.
+	pub fn parse(code: &str) -> Option<ReturnCode> {
+		if code.len() > RETURN_CODE_MARKER.len() && code.ends_with(RETURN_CODE_MARKER) {
+			Some(ReturnCode(code.to_string()))
+		} else {
+			None
+		}
+	}
+
+	/// Get the return code as it is sent to the server.
+	pub fn as_str(&self) -> &str { &self.0 }
+}
+
+#[derive(Default)]
+struct State {
+	result: Option<Result<(), Error>>,
+	waker: Option<Waker>,
+}
+
+/// The other end of a `Request`, it is stored until the answer arrives.
+struct Sender {
+	server_id: ServerId,
+	state: Arc<Mutex<State>>,
+}
+
+impl Sender {
+	fn send(self, result: Result<(), Error>) {
+		let mut state = self.state.lock().unwrap();
+		state.result = Some(result);
+		if let Some(waker) = state.waker.take() {
+			waker.wake();
+		}
+	}
+}
+
+/// A request that resolves when the server answered it.
+///
+/// It resolves to `Error::NotConnected` if the connection to the server is
+/// closed before the answer arrives.
+pub struct Request {
+	state: Arc<Mutex<State>>,
+}
+
+impl Request {
+	/// Create a return code, call `send` with it and wait for the answer.
+	///
+	/// If `send` fails, the request resolves to that error immediately.
+	pub(crate) fn send<F: FnOnce(*const c_char) -> Error>(
+		plugin_id: &str, server_id: ServerId, send: F,
+	) -> Request {
+		let return_code = ReturnCode::create(plugin_id);
+		let state = Arc::new(Mutex::new(State::default()));
+		PENDING
+			.lock()
+			.unwrap()
+			.insert(return_code.clone(), Sender { server_id, state: state.clone() });
+
+		let res = send(to_cstring!(return_code.as_str()).as_ptr());
+		if res != Error::Ok {
+			resolve(&return_code, Err(res));
+		}
+		Request { state }
+	}
+}
+
+impl Future for Request {
+	type Output = Result<(), Error>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+		let mut state = self.state.lock().unwrap();
+		match state.result.take() {
+			Some(result) => Poll::Ready(result),
+			None => {
+				state.waker = Some(cx.waker().clone());
+				Poll::Pending
+			}
+		}
+	}
+}
+
+/// Resolve the request that waits for `return_code`.
+///
+/// Returns `false` if no request waits for this return code.
+pub(crate) fn resolve(return_code: &ReturnCode, result: Result<(), Error>) -> bool {
+	// Release the lock before waking the request
+	let sender = PENDING.lock().unwrap().remove(return_code);
+	match sender {
+		Some(sender) => {
+			sender.send(result);
+			true
+		}
+		None => false,
+	}
+}
+
+/// Fail all requests that wait for an answer from a server.
+pub(crate) fn cancel_server(server_id: ServerId) {
+	let senders: Vec<_> = {
+		let mut pending = PENDING.lock().unwrap();
+		let codes: Vec<_> = pending
+			.iter()
+			.filter(|&(_, s)| s.server_id == server_id)
+			.map(|(c, _)| c.clone())
+			.collect();
+		codes.iter().filter_map(|c| pending.remove(c)).collect()
+	};
+	for sender in senders {
+		sender.send(Err(Error::NotConnected));
+	}
+}
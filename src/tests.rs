@@ -0,0 +1,148 @@
+//! Tests for the caches and state that do not need TeamSpeak.
+
+use time::duration_from_secs;
+use {
+	AfkChange, ChannelData, ChannelId, ConnectionData, ConnectionId, Map, ServerData, ServerId,
+	TsApi,
+};
+
+const SERVER_ID: ServerId = ServerId(1);
+
+/// Create a server with `count` channels, every channel is a subchannel of
+/// the channel with a tenth of its id.
+fn server_with_channels(count: u64) -> ServerData {
+	let mut server = ServerData::new(SERVER_ID);
+	let mut channels = Map::new();
+	for id in 1..=count {
+		let mut channel = ChannelData::new(SERVER_ID, ChannelId(id));
+		channel.parent_channel_id = Ok(ChannelId(id / 10));
+		channels.insert(ChannelId(id), channel);
+	}
+	server.channels = Ok(channels);
+	server.subchannels = ServerData::index_subchannels(&server.channels);
+	server
+}
+
+/// Sort the subchannels of an index and remove empty entries, so indices
+/// can be compared.
+fn sorted_index(index: &Map<ChannelId, Vec<ChannelId>>) -> Map<ChannelId, Vec<ChannelId>> {
+	let mut index = index.clone();
+	for subchannels in index.values_mut() {
+		subchannels.sort_by_key(|c| c.0);
+	}
+	index.retain(|_, subchannels| !subchannels.is_empty());
+	index
+}
+
+#[test]
+fn subchannel_index_updates_like_rebuild() {
+	let mut server = server_with_channels(500);
+	assert_eq!(server.subchannels[&ChannelId(0)].len(), 9);
+	assert_eq!(server.subchannels[&ChannelId(1)].len(), 10);
+
+	// Move channels around, also to the parent they already have
+	for id in (20..500).step_by(7) {
+		server.set_channel_parent(ChannelId(id), ChannelId(id % 13));
+	}
+	server.set_channel_parent(ChannelId(42), ChannelId(3));
+	server.set_channel_parent(ChannelId(42), ChannelId(3));
+	for id in (100..500).step_by(11) {
+		server.remove_channel(ChannelId(id));
+	}
+
+	let rebuilt = ServerData::index_subchannels(&server.channels);
+	assert_eq!(sorted_index(&server.subchannels), sorted_index(&rebuilt));
+}
+
+#[test]
+fn updated_parent_keeps_subchannels() {
+	// Channel updates remove and add the channel again
+	let mut server = server_with_channels(500);
+	for id in &[0, 1, 4, 42] {
+		let channel = server.remove_channel(ChannelId(*id));
+		if let Some(channel) = channel {
+			server.insert_channel(channel).unwrap();
+		}
+	}
+	assert_eq!(server.subchannels[&ChannelId(1)].len(), 10);
+
+	let rebuilt = ServerData::index_subchannels(&server.channels);
+	assert_eq!(sorted_index(&server.subchannels), sorted_index(&rebuilt));
+}
+
+#[test]
+fn deleted_channel_is_removed_from_index() {
+	let mut server = server_with_channels(500);
+	for id in 420..430 {
+		server.delete_channel(ChannelId(id));
+	}
+	server.delete_channel(ChannelId(42));
+	assert!(!server.subchannels.contains_key(&ChannelId(42)));
+	assert!(!server.subchannels[&ChannelId(4)].contains(&ChannelId(42)));
+
+	let rebuilt = ServerData::index_subchannels(&server.channels);
+	assert_eq!(sorted_index(&server.subchannels), sorted_index(&rebuilt));
+}
+
+#[test]
+fn subchannel_is_inserted_once() {
+	let mut subchannels = Map::new();
+	ServerData::insert_subchannel(&mut subchannels, ChannelId(0), ChannelId(5));
+	ServerData::insert_subchannel(&mut subchannels, ChannelId(0), ChannelId(5));
+	assert_eq!(subchannels[&ChannelId(0)], vec![ChannelId(5)]);
+}
+
+#[test]
+fn moved_connection_changes_channel() {
+	// Moves with every visibility update the cached connection like this
+	let mut server = ServerData::new(SERVER_ID);
+	let mut connection = ConnectionData::new(SERVER_ID, ConnectionId(3));
+	connection.channel_id = Ok(ChannelId(1));
+	server.visible_connections.insert(ConnectionId(3), connection);
+
+	server.move_connection(ConnectionId(3), ChannelId(2));
+	assert_eq!(server.visible_connections[&ConnectionId(3)].channel_id, Ok(ChannelId(2)));
+
+	// Connections that are not cached are not added
+	server.move_connection(ConnectionId(4), ChannelId(2));
+	assert!(!server.visible_connections.contains_key(&ConnectionId(4)));
+}
+
+#[test]
+fn connection_is_removed_once() {
+	// A kicked connection is only removed from the cache by the kick event, a
+	// second removal of the same connection must not fail
+	let mut server = ServerData::new(SERVER_ID);
+	for id in 1..=2 {
+		server
+			.visible_connections
+			.insert(ConnectionId(id), ConnectionData::new(SERVER_ID, ConnectionId(id)));
+	}
+
+	assert!(server.remove_connection(ConnectionId(1)).is_some());
+	assert!(server.remove_connection(ConnectionId(1)).is_none());
+	assert_eq!(server.visible_connections.keys().collect::<Vec<_>>(), vec![&ConnectionId(2)]);
+}
+
+#[test]
+fn afk_state_changes_with_idle_time() {
+	let api = TsApi::new(String::from("test"), String::from("test"));
+	let connection_id = ConnectionId(3);
+	let update = |secs| api.update_afk(SERVER_ID, connection_id, duration_from_secs(secs));
+	// Disabled
+	assert_eq!(update(600), None);
+
+	*api.afk_threshold.borrow_mut() = Some(duration_from_secs(300));
+	assert_eq!(update(10), None);
+	assert_eq!(update(300), Some(AfkChange::WentAfk));
+	assert_eq!(update(310), None);
+	assert_eq!(update(0), Some(AfkChange::Returned));
+	assert_eq!(update(20), None);
+	assert_eq!(update(400), Some(AfkChange::WentAfk));
+	// Other connections are independent
+	assert_eq!(
+		api.update_afk(SERVER_ID, ConnectionId(4), duration_from_secs(400)),
+		Some(AfkChange::WentAfk)
+	);
+	assert_eq!(update(1), Some(AfkChange::Returned));
+}
@@ -0,0 +1,78 @@
+//! Durations and points in time.
+//!
+//! With the `chrono` feature, which is enabled by default, durations are
+//! `chrono::Duration` and points in time are `chrono::DateTime<Utc>`.
+//! Without it, durations are `std::time::Duration` and points in time are
+//! unix timestamps in seconds.
+//!
+//! The rest of the library converts the values from TeamSpeak only with the
+//! functions of this module, so it does not depend on the feature.
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "chrono")]
+pub use chrono::Duration;
+#[cfg(not(feature = "chrono"))]
+pub use std::time::Duration;
+
+/// A point in time.
+#[cfg(feature = "chrono")]
+pub type Timestamp = DateTime<Utc>;
+/// A point in time, as unix timestamp in seconds.
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = u64;
+
+#[cfg(feature = "chrono")]
+pub(crate) fn duration_from_secs(secs: u64) -> Duration { Duration::seconds(secs as i64) }
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn duration_from_secs(secs: u64) -> Duration { Duration::from_secs(secs) }
+
+#[cfg(feature = "chrono")]
+pub(crate) fn duration_from_millis(millis: u64) -> Duration {
+	Duration::milliseconds(millis as i64)
+}
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn duration_from_millis(millis: u64) -> Duration { Duration::from_millis(millis) }
+
+/// Convert a duration for the standard library.
+///
+/// Returns `None` if the duration is negative.
+#[cfg(feature = "chrono")]
+pub(crate) fn duration_to_std(duration: Duration) -> Option<std::time::Duration> {
+	duration.to_std().ok()
+}
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn duration_to_std(duration: Duration) -> Option<std::time::Duration> { Some(duration) }
+
+/// Convert a unix timestamp from TeamSpeak.
+///
+/// Returns `None` if chrono cannot represent the timestamp.
+#[cfg(feature = "chrono")]
+pub(crate) fn timestamp_from_secs(secs: u64) -> Option<Timestamp> {
+	DateTime::from_timestamp(secs as i64, 0)
+}
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn timestamp_from_secs(secs: u64) -> Option<Timestamp> { Some(secs) }
+
+/// Get the unix timestamp of a point in time.
+#[cfg(feature = "chrono")]
+pub(crate) fn timestamp_as_secs(timestamp: &Timestamp) -> i64 { timestamp.timestamp() }
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn timestamp_as_secs(timestamp: &Timestamp) -> i64 { *timestamp as i64 }
+
+#[cfg(feature = "chrono")]
+pub(crate) fn now() -> Timestamp { Utc::now() }
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn now() -> Timestamp {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or(0)
+}
+
+/// Get the point in time that lies `duration` before now.
+#[cfg(feature = "chrono")]
+pub(crate) fn ago(duration: Duration) -> Timestamp { Utc::now() - duration }
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn ago(duration: Duration) -> Timestamp { now().saturating_sub(duration.as_secs()) }
@@ -1,8 +1,12 @@
 use std::ffi::CStr;
 use std::mem::transmute;
 use std::os::raw::{c_char, c_int, c_short, c_uint};
+use std::panic::{self, AssertUnwindSafe};
 use std::slice;
-use std::sync::Mutex;
+use std::sync::mpsc::SyncSender;
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
 
 use ts3plugin_sys::public_definitions::*;
 use ts3plugin_sys::ts3functions::Ts3Functions;
@@ -13,6 +17,80 @@ lazy_static! {
 	/// The api, plugin and plugin id
 	pub(crate) static ref DATA: Mutex<(Option<(::TsApi, Box<dyn Plugin>)>, Option<String>)> =
 		Mutex::new((None, None));
+	/// The sending end of the audio buffer, if it is enabled.
+	pub(crate) static ref AUDIO_BUFFER: RwLock<Option<SyncSender<::AudioPacket>>> =
+		RwLock::new(None);
+	/// The thread that calls the timers.
+	static ref TIMER_THREAD: Mutex<WorkerThread> = Mutex::new(WorkerThread::default());
+	/// The thread that detects connections that are away from keyboard.
+	static ref AFK_THREAD: Mutex<WorkerThread> = Mutex::new(WorkerThread::default());
+}
+
+/// A thread of this library, which has to be stopped before TeamSpeak
+/// unloads the plugin.
+#[derive(Default)]
+struct WorkerThread {
+	handle: Option<thread::JoinHandle<()>>,
+	/// Set to `false` by the thread when it stops by itself. It still has to
+	/// be joined afterwards.
+	running: bool,
+}
+
+impl WorkerThread {
+	/// Start the thread if it is not running.
+	///
+	/// Returns `false` if it was running already.
+	fn start<F: FnOnce() + Send + 'static>(&mut self, f: F) -> bool {
+		if self.running {
+			return false;
+		}
+		// The old thread does not take any lock after it stopped running
+		if let Some(handle) = self.handle.take() {
+			let _ = handle.join();
+		}
+		self.handle = Some(thread::spawn(f));
+		self.running = true;
+		true
+	}
+
+	/// Wake the thread up if it is waiting.
+	fn unpark(&self) {
+		if let Some(ref handle) = self.handle {
+			handle.thread().unpark();
+		}
+	}
+
+	/// Take the thread out, so it can be joined without holding the lock.
+	///
+	/// The thread is woken up, it has to stop when the plugin data is gone.
+	fn stop(&mut self) -> Option<thread::JoinHandle<()>> {
+		self.running = false;
+		let handle = self.handle.take();
+		if let Some(ref handle) = handle {
+			handle.thread().unpark();
+		}
+		handle
+	}
+}
+
+/// Push voice data into the audio buffer.
+///
+/// Returns `false` if the audio buffer is not enabled.
+fn buffer_audio(
+	server_id: ::ServerId, connection_id: Option<::ConnectionId>, samples: &[i16], channels: c_int,
+) -> bool {
+	if let Some(ref sender) = *AUDIO_BUFFER.read().unwrap() {
+		// Drop the packet if the buffer is full
+		let _ = sender.try_send(::AudioPacket::new(
+			server_id,
+			connection_id,
+			samples.to_vec(),
+			channels,
+		));
+		true
+	} else {
+		false
+	}
 }
 
 /// Get the current file without the preceding path
@@ -25,26 +103,86 @@ macro_rules! filename {
 
 /// Log an error with a description and the current line and file
 macro_rules! error {
-	($api: ident, $description: expr, $error: expr) => {
+	($api: ident, $description: expr, $error: expr) => {{
+		let error = $error;
 		$api.log_or_print(
-			format!("Error {:?} ({}) in in {}:L{}", $error, $description, filename!(), line!()),
+			format!(
+				"Error {:?}: {} ({}) in {}:L{}",
+				error,
+				$api.get_error_message(error),
+				$description,
+				filename!(),
+				line!()
+			),
 			"rust-ts3plugin",
 			::LogLevel::Error,
 		);
+	}};
+}
+
+/// Get the api and the plugin out of the locked `DATA`.
+///
+/// Returns early from the calling event shim if the plugin is not loaded,
+/// e.g. when an event is still in flight while the plugin is shut down.
+macro_rules! plugin_data {
+	($data: ident) => {
+		plugin_data!($data, ())
+	};
+	($data: ident, $ret: expr) => {
+		match $data.0.as_mut() {
+			Some(data) => data,
+			None => {
+				::TsApi::static_log_or_print(
+					format!(
+						"Ignoring event in {}:L{}, the plugin is not loaded",
+						filename!(),
+						line!()
+					),
+					"rust-ts3plugin",
+					::LogLevel::Debug,
+				);
+				return $ret;
+			}
+		}
+	};
+}
+
+/// Get a mutable server out of the api.
+///
+/// Returns early from the calling event shim if the server is not known,
+/// e.g. when an event arrives for a connection that is not established.
+macro_rules! server_data {
+	($api: ident, $server_id: expr) => {
+		match $api.get_mut_server($server_id) {
+			Some(server) => server,
+			None => {
+				::TsApi::static_log_or_print(
+					format!(
+						"Ignoring event in {}:L{}, the server {:?} is not known",
+						filename!(),
+						line!(),
+						$server_id
+					),
+					"rust-ts3plugin",
+					::LogLevel::Debug,
+				);
+				return;
+			}
+		}
 	};
 }
 
 /// Initialises the internal data.
-/// T is the plugin type.
+/// T is the plugin type, `plugin_name` the name that is shown in TeamSpeak.
 /// This function will be called from `create_plugin!`, please don't call it manually.
 #[doc(hidden)]
-pub unsafe fn private_init<T: Plugin>() -> Result<(), ::InitError> {
+pub unsafe fn private_init<T: Plugin>(plugin_name: String) -> Result<(), ::InitError> {
 	// Create the TsApi
 	let plugin_id = {
 		let mut data = DATA.lock().unwrap();
 		data.1.take().unwrap()
 	};
-	let mut api = ::TsApi::new(plugin_id);
+	let mut api = ::TsApi::new(plugin_id, plugin_name);
 	if let Err(error) = api.load() {
 		error!(api, "Can't create TsApi", error);
 		return Err(::InitError::Failure);
@@ -54,13 +192,178 @@ pub unsafe fn private_init<T: Plugin>() -> Result<(), ::InitError> {
 	match T::new(&mut api) {
 		Ok(plugin) => {
 			let mut data = DATA.lock().unwrap();
+			// Timers that were scheduled before the api was stored could not
+			// run yet
+			let has_timers = api.has_timers();
 			data.0 = Some((api, plugin));
+			if has_timers {
+				start_timer_thread();
+			}
 			Ok(())
 		}
 		Err(error) => Err(error),
 	}
 }
 
+/// Start the thread that detects connections that are away from keyboard,
+/// if it is not running yet.
+///
+/// The thread stops when the detection is disabled or the plugin is unloaded.
+pub(crate) fn start_afk_timer() {
+	AFK_THREAD.lock().unwrap().start(|| {
+		loop {
+			// Woken up early when the plugin is unloaded
+			thread::park_timeout(Duration::from_secs(10));
+			if !catch_panic("afk timer", true, check_afk) {
+				break;
+			}
+		}
+	});
+}
+
+/// Check the connections for the afk detection.
+///
+/// Returns `false` if the thread should stop.
+fn check_afk() -> bool {
+	let mut data = DATA.lock().unwrap();
+	let running = match data.0.as_mut() {
+		Some(data) => check_afk_connections(&data.0),
+		None => false,
+	};
+	if !running {
+		// Still holding the lock, so a new threshold will start a new thread
+		AFK_THREAD.lock().unwrap().running = false;
+	}
+	running
+}
+
+/// Request the connection info of all connections for the afk detection.
+///
+/// TeamSpeak keeps the connection info until it is requested again, so it
+/// is refreshed every round. The idle times are checked when the new
+/// connection info arrives in `ts3plugin_onConnectionInfoEvent`.
+///
+/// Returns `false` if the detection is disabled.
+fn check_afk_connections(api: &::TsApi) -> bool {
+	if api.afk_threshold.borrow().is_none() {
+		return false;
+	}
+	for server in api.get_servers() {
+		for connection in server.get_connections() {
+			if let Err(error) = connection.request_connection_info() {
+				error!(api, "Can't request connection info for the afk detection", error);
+			}
+		}
+	}
+	// Forget connections that left
+	api.afk_connections
+		.borrow_mut()
+		.retain(|&(s, c)| api.get_server(s).and_then(|s| s.get_connection(c)).is_some());
+	true
+}
+
+/// Compare the idle time of a connection with the afk threshold and notify
+/// the plugin if the connection went away or returned.
+fn check_afk_connection(
+	api: &::TsApi, plugin: &mut Box<dyn Plugin>, server: &::Server, connection: &::Connection,
+) {
+	if api.afk_threshold.borrow().is_none() {
+		return;
+	}
+	// Read the new connection info without requesting it again
+	let idle_time = match ::ConnectionData::get_connection_property_as_uint64(
+		server.get_id(),
+		connection.get_id(),
+		::ConnectionProperties::IdleTime,
+	) {
+		Ok(idle_time) => ::time::duration_from_secs(idle_time),
+		Err(_) => return,
+	};
+	match api.update_afk(server.get_id(), connection.get_id(), idle_time) {
+		Some(::AfkChange::WentAfk) => plugin.client_went_afk(api, server, connection, idle_time),
+		Some(::AfkChange::Returned) => plugin.client_returned(api, server, connection),
+		None => {}
+	}
+}
+
+/// Start the thread that calls the timers of the api or wake it up, so it
+/// notices new timers.
+///
+/// The thread stops when no timers are left or the plugin is unloaded.
+pub(crate) fn start_timer_thread() {
+	let mut timer_thread = TIMER_THREAD.lock().unwrap();
+	let started = timer_thread.start(|| {
+		while let Some(wait) = catch_panic("timer", Some(Duration::from_secs(0)), run_timers) {
+			thread::park_timeout(wait);
+		}
+	});
+	if !started {
+		timer_thread.unpark();
+	}
+}
+
+/// Call the timers that are due.
+///
+/// Returns how long to wait for the next timer or `None` if the thread
+/// should stop.
+fn run_timers() -> Option<Duration> {
+	let mut data = DATA.lock().unwrap();
+	let wait = data.0.as_mut().and_then(|data| data.0.run_timers());
+	if wait.is_none() {
+		// Still holding the lock, so a new timer will start a new thread
+		TIMER_THREAD.lock().unwrap().running = false;
+	}
+	wait
+}
+
+/// Record that a connection joined, left or switched the channel in the
+/// event log of the api.
+fn log_move_event(
+	api: &::TsApi, connection: &::Connection, old_channel_id: ::ChannelId,
+	new_channel_id: ::ChannelId, message: &str,
+) {
+	let server_id = connection.get_server_id();
+	let connection_id = connection.get_id();
+	let name = connection.get_name().map(|n| n.to_string()).unwrap_or_default();
+	let event = if old_channel_id == ::ChannelId(0) {
+		::Event::Joined { server_id, connection_id, name }
+	} else if new_channel_id == ::ChannelId(0) {
+		::Event::Left { server_id, connection_id, name, message: message.to_string() }
+	} else {
+		::Event::Moved { server_id, connection_id, name, old_channel_id, new_channel_id }
+	};
+	api.log_event(event);
+}
+
+/// Call `f` and return `default` if it panics.
+///
+/// A panic must not unwind into TeamSpeak, so every callback runs inside this
+/// function. The panic gets logged and the lock of the plugin data is
+/// released from poisoning, so the following callbacks still reach the
+/// plugin.
+#[doc(hidden)]
+pub fn catch_panic<R, F: FnOnce() -> R>(name: &str, default: R, f: F) -> R {
+	match panic::catch_unwind(AssertUnwindSafe(f)) {
+		Ok(res) => res,
+		Err(payload) => {
+			let message = if let Some(s) = payload.downcast_ref::<&str>() {
+				*s
+			} else if let Some(s) = payload.downcast_ref::<String>() {
+				s.as_str()
+			} else {
+				"Unknown panic"
+			};
+			::TsApi::static_log_or_print(
+				format!("Panic in {}: {}", name, message),
+				"rust-ts3plugin",
+				::LogLevel::Critical,
+			);
+			DATA.clear_poison();
+			default
+		}
+	}
+}
+
 // ************************** Interface for TeamSpeak **************************
 
 #[allow(non_snake_case)]
@@ -79,22 +382,39 @@ pub unsafe extern "C" fn ts3plugin_setFunctionPointers(funs: Ts3Functions) {
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_shutdown() {
-	let mut data = DATA.lock().unwrap();
-	if let Some(data) = data.0.as_mut() {
-		let api = &mut data.0;
-		let plugin = &mut data.1;
-		plugin.shutdown(api);
-	}
-	// Drop the api and the plugin
-	*data = (None, None);
+	catch_panic("ts3plugin_shutdown", (), || {
+		let mut data = DATA.lock().unwrap();
+		if let Some(data) = data.0.as_mut() {
+			let api = &mut data.0;
+			let plugin = &mut data.1;
+			let reason = if api.get_servers().is_empty() {
+				::ShutdownReason::LikelyClientExit
+			} else {
+				::ShutdownReason::LikelyUserDisabled
+			};
+			plugin.shutdown(api, reason);
+		}
+		// Drop the api and the plugin
+		*data = (None, None);
+		*AUDIO_BUFFER.write().unwrap() = None;
+		drop(data);
+
+		// The threads must not run anymore when TeamSpeak unloads the library
+		let threads = vec![AFK_THREAD.lock().unwrap().stop(), TIMER_THREAD.lock().unwrap().stop()];
+		for thread in threads.into_iter().flatten() {
+			let _ = thread.join();
+		}
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_registerPluginID(plugin_id: *const c_char) {
-	let mut data = DATA.lock().unwrap();
-	data.1 = Some(to_string!(plugin_id));
+	catch_panic("ts3plugin_registerPluginID", (), || {
+		let mut data = DATA.lock().unwrap();
+		data.1 = Some(to_string!(plugin_id));
+	})
 }
 
 #[allow(non_snake_case)]
@@ -103,41 +423,63 @@ pub unsafe extern "C" fn ts3plugin_registerPluginID(plugin_id: *const c_char) {
 pub unsafe extern "C" fn ts3plugin_onConnectStatusChangeEvent(
 	server_id: u64, status: c_int, error: c_uint,
 ) {
-	let server_id = ::ServerId(server_id);
-	let status = transmute(status);
-	let error = transmute(error);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	// Add the server if we can get information about it
-	// and don't have that server cached already.
-	if status != ConnectStatus::Connecting && api.get_server(server_id).is_none() {
-		api.add_server(server_id);
-	}
-	{
-		let server = api.get_server_unwrap(server_id);
-		// Execute plugin callback
-		plugin.connect_status_change(api, &server, status, error);
-	}
-	// Remove server if we disconnected
-	if status == ConnectStatus::Disconnected {
-		api.remove_server(server_id);
-	}
+	catch_panic("ts3plugin_onConnectStatusChangeEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let status = transmute(status);
+		let error = transmute(error);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		// Add the server if we can get information about it
+		// and don't have that server cached already.
+		if status != ConnectStatus::Connecting && api.get_server(server_id).is_none() {
+			api.add_server(server_id);
+		}
+		{
+			let server = api.get_server_unwrap(server_id);
+			let error_message = match error {
+				::Error::Ok => None,
+				_ => Some(api.get_error_message(error)),
+			};
+			// Execute plugin callback
+			let reason = if status == ConnectStatus::Disconnected {
+				Some(::DisconnectReason::from_error(error))
+			} else {
+				None
+			};
+			plugin.connect_status_change(api, &server, status, error, error_message, reason);
+		}
+		// Remove server if we disconnected
+		if status == ConnectStatus::Disconnected {
+			api.remove_server(server_id);
+			#[cfg(feature = "async")]
+			::request::cancel_server(server_id);
+			// Connect again if the disconnect came from `Server::reconnect`
+			let reconnect = api.reconnects.borrow_mut().remove(&server_id);
+			if let Some(reconnect) = reconnect {
+				if let Err(error) = reconnect.start(server_id) {
+					error!(api, "Can't reconnect", error);
+				}
+			}
+		}
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_onServerStopEvent(server_id: u64, message: *const c_char) {
-	let server_id = ::ServerId(server_id);
-	let message = to_string!(message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	plugin.server_stop(api, &server, message);
+	catch_panic("ts3plugin_onServerStopEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let message = to_string!(message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		plugin.server_stop(api, &server, message);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -147,18 +489,33 @@ pub unsafe extern "C" fn ts3plugin_onServerErrorEvent(
 	server_id: u64, message: *const c_char, error: c_uint, return_code: *const c_char,
 	extra_message: *const c_char,
 ) -> c_int {
-	let server_id = ::ServerId(server_id);
-	let message = to_string!(message);
-	let error = transmute(error);
-	let return_code = to_string!(return_code);
-	let extra_message = to_string!(extra_message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let b = plugin.server_error(api, &server, error, message, return_code, extra_message);
-	if b { 1 } else { 0 }
+	catch_panic("ts3plugin_onServerErrorEvent", 0, || {
+		let server_id = ::ServerId(server_id);
+		let message = to_string!(message);
+		let error = transmute(error);
+		let return_code = to_string!(return_code);
+		let extra_message = to_string!(extra_message);
+		#[cfg(feature = "async")]
+		{
+			let result = match error {
+				::Error::Ok => Ok(()),
+				_ => Err(error),
+			};
+			// Answers to own requests are handled by the request
+			if let Some(code) = ::request::ReturnCode::parse(&return_code) {
+				if ::request::resolve(&code, result) {
+					return 1;
+				}
+			}
+		}
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data, 0);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let b = plugin.server_error(api, &server, error, message, return_code, extra_message);
+		if b { 1 } else { 0 }
+	})
 }
 
 #[allow(non_snake_case)]
@@ -167,56 +524,132 @@ pub unsafe extern "C" fn ts3plugin_onServerErrorEvent(
 pub unsafe extern "C" fn ts3plugin_onServerEditedEvent(
 	server_id: u64, invoker_id: u16, invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let invoker = if invoker_id == 0 {
-		None
-	} else {
-		Some(::InvokerData::new(
-			::ConnectionId(invoker_id),
-			to_string!(invoker_uid),
-			to_string!(invoker_name),
-		))
-	};
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	if let Some(ref invoker) = invoker {
-		api.try_update_invoker(server_id, invoker);
-	}
-	if let Some(ref mut server) = api.get_mut_server(server_id) {
-		server.update();
-	}
-	let server = api.get_server_unwrap(server_id);
-	plugin.server_edited(api, &server, invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref());
+	catch_panic("ts3plugin_onServerEditedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let invoker = if invoker_id == 0 {
+			None
+		} else {
+			Some(::InvokerData::new(
+				::ConnectionId(invoker_id),
+				to_string!(invoker_uid),
+				to_string!(invoker_name),
+			))
+		};
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Some(ref invoker) = invoker {
+			api.try_update_invoker(server_id, invoker);
+		}
+		if let Some(ref mut server) = api.get_mut_server(server_id) {
+			server.update();
+		}
+		let server = api.get_server_unwrap(server_id);
+		plugin.server_edited(
+			api,
+			&server,
+			invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
+		);
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_onServerConnectionInfoEvent(server_id: u64) {
-	let server_id = ::ServerId(server_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	plugin.server_connection_info(api, &server);
+	catch_panic("ts3plugin_onServerConnectionInfoEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		plugin.server_connection_info(api, &server);
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_onConnectionInfoEvent(server_id: u64, connection_id: u16) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let connection = server.get_connection_unwrap(connection_id);
-	plugin.connection_info(api, &server, &connection);
+	catch_panic("ts3plugin_onConnectionInfoEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let connection = server.get_connection_unwrap(connection_id);
+		// Before the plugin can discard the connection info
+		check_afk_connection(api, plugin, &server, &connection);
+		plugin.connection_info(api, &server, &connection);
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onClientIDsEvent(
+	server_id: u64, uid: *const c_char, connection_id: u16, name: *const c_char,
+) {
+	catch_panic("ts3plugin_onClientIDsEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		plugin.client_ids(api, &server, to_string!(uid), connection_id, to_string!(name));
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onServerGroupByClientIDEvent(
+	server_id: u64, name: *const c_char, server_group_id: u64, database_id: u64,
+) {
+	catch_panic("ts3plugin_onServerGroupByClientIDEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let server_group_id = ::ServerGroupId(server_group_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let icon_id = server.get_server_group(server_group_id).map_or(0, |g| g.get_icon_id());
+		let group = ::ServerGroup::new(server_group_id, to_string!(name), icon_id);
+		let groups = match server.data {
+			Ok(data) => {
+				let mut all_groups = data.client_server_groups.borrow_mut();
+				let groups = all_groups.entry(database_id).or_default();
+				groups.push(group);
+				groups.clone()
+			}
+			Err(_) => vec![group],
+		};
+		let connection =
+			server.get_connections().into_iter().find(|c| c.get_database_id() == Ok(database_id));
+		plugin.server_groups_by_client(api, &server, connection.as_ref(), database_id, &groups);
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onClientIDsFinishedEvent(server_id: u64) {
+	catch_panic("ts3plugin_onClientIDsFinishedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		plugin.client_ids_finished(api, &server);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -226,41 +659,67 @@ pub unsafe extern "C" fn ts3plugin_onUpdateClientEvent(
 	server_id: u64, connection_id: u16, invoker_id: u16, invoker_name: *const c_char,
 	invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-
-	// Save the old connection
-	let old_connection;
-	{
-		let server = api.get_mut_server(server_id).unwrap();
-		// Try to get the old channel
-		old_connection = server
-			.remove_connection(connection_id)
-			.unwrap_or(::ConnectionData::new(server_id, connection_id));
-		let connection = server.add_connection(connection_id);
-		// Copy optional data from old connection
-		connection.update_from(&old_connection);
-	}
-	let server = api.get_server_unwrap(server_id);
-	let connection = server.get_connection_unwrap(connection_id);
-	let old_connection = ::Connection::new(api, &old_connection);
-	plugin.connection_properties_changed(
-		api,
-		&server,
-		&connection,
-		&old_connection,
-		::get_connection_changes(old_connection.properties(), connection.properties()),
-		&::Invoker::new(server.clone(), invoker),
-	);
+	catch_panic("ts3plugin_onUpdateClientEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		// Wake up waiting threads before locking, they probably hold the lock
+		#[cfg(feature = "blocking")]
+		::blocking::connection_updated(server_id, connection_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+
+		// Save the old connection
+		let old_connection;
+		{
+			let server = server_data!(api, server_id);
+			// Try to get the old channel
+			old_connection = server
+				.remove_connection(connection_id)
+				.unwrap_or(::ConnectionData::new(server_id, connection_id));
+			let connection = server.add_connection(connection_id);
+			// Copy optional data from old connection
+			connection.update_from(&old_connection);
+			connection.talking_since = old_connection.talking_since;
+			// The variables arrive with an update after they were requested
+			connection.variables_received =
+				old_connection.variables_received || old_connection.variables_requested.get();
+		}
+		let server = api.get_server_unwrap(server_id);
+		let connection = server.get_connection_unwrap(connection_id);
+		let old_connection = ::Connection::new(api, &old_connection);
+		let changes =
+			::get_connection_changes(old_connection.properties(), connection.properties());
+		let own_changes = if server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id) {
+			Some(changes.clone())
+		} else {
+			None
+		};
+		plugin.connection_properties_changed(
+			api,
+			&server,
+			&connection,
+			&old_connection,
+			changes,
+			&::Invoker::new(server.clone(), invoker),
+		);
+		if let Some(changes) = own_changes {
+			plugin.own_properties_changed(api, &server, &old_connection, &connection, changes);
+		}
+		if let (Ok(old_recording), Ok(recording)) =
+			(old_connection.get_recording(), connection.get_recording())
+		{
+			if old_recording != recording {
+				plugin.recording_changed(api, &server, &connection, recording);
+			}
+		}
+	})
 }
 
 #[allow(non_snake_case)]
@@ -270,82 +729,89 @@ pub unsafe extern "C" fn ts3plugin_onClientMoveEvent(
 	server_id: u64, connection_id: u16, old_channel_id: u64, new_channel_id: u64,
 	visibility: c_int, move_message: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let old_channel_id = ::ChannelId(old_channel_id);
-	let new_channel_id = ::ChannelId(new_channel_id);
-	let visibility = transmute(visibility);
-	let move_message = to_string!(move_message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	if old_channel_id == ::ChannelId(0) {
-		// Connection connected, this will also be called for ourselves
-		api.get_mut_server(server_id).unwrap().add_connection(connection_id);
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		plugin.connection_changed(api, &server, &connection, true, move_message)
-	} else if new_channel_id == ::ChannelId(0) {
-		// Connection disconnected
-		{
+	catch_panic("ts3plugin_onClientMoveEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let old_channel_id = ::ChannelId(old_channel_id);
+		let new_channel_id = ::ChannelId(new_channel_id);
+		let visibility = transmute(visibility);
+		let move_message = to_string!(move_message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if old_channel_id == ::ChannelId(0) {
+			// Connection connected, this will also be called for ourselves
+			server_data!(api, server_id).add_connection(connection_id);
 			let server = api.get_server_unwrap(server_id);
 			let connection = server.get_connection_unwrap(connection_id);
-			plugin.connection_changed(api, &server, &connection, false, move_message);
-		}
-		api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
-	} else if old_channel_id == new_channel_id {
-		// Connection announced
-		match visibility {
-			Visibility::Enter => {
-				api.get_mut_server(server_id).unwrap().add_connection(connection_id);
+			let is_self = server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id);
+			log_move_event(api, &connection, old_channel_id, new_channel_id, &move_message);
+			plugin.connection_changed(api, &server, &connection, true, is_self, move_message)
+		} else if new_channel_id == ::ChannelId(0) {
+			// Connection disconnected
+			{
 				let server = api.get_server_unwrap(server_id);
 				let connection = server.get_connection_unwrap(connection_id);
-				plugin.connection_announced(api, &server, &connection, true);
+				let is_self = server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id);
+				log_move_event(api, &connection, old_channel_id, new_channel_id, &move_message);
+				plugin.connection_changed(api, &server, &connection, false, is_self, move_message);
 			}
-			Visibility::Leave => {
-				{
+			server_data!(api, server_id).remove_connection(connection_id);
+		} else if old_channel_id == new_channel_id {
+			// Connection announced
+			match visibility {
+				Visibility::Enter => {
+					server_data!(api, server_id).add_connection(connection_id);
 					let server = api.get_server_unwrap(server_id);
 					let connection = server.get_connection_unwrap(connection_id);
-					plugin.connection_announced(api, &server, &connection, false);
+					plugin.connection_announced(api, &server, &connection, true, None);
 				}
-				api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
+				Visibility::Leave => {
+					{
+						let server = api.get_server_unwrap(server_id);
+						let connection = server.get_connection_unwrap(connection_id);
+						plugin.connection_announced(api, &server, &connection, false, None);
+					}
+					server_data!(api, server_id).remove_connection(connection_id);
+				}
+				// The channel did not change, a move between different channels
+				// with retained visibility is a channel switch below
+				Visibility::Retain => {}
 			}
-			Visibility::Retain => {}
-		}
-	} else {
-		// Connection switched channel
-		// Add the connection if it entered visibility
-		if visibility == Visibility::Enter {
-			api.get_mut_server(server_id).unwrap().add_connection(connection_id);
-		}
-		// Update the channel
-		{
-			if let Some(connection) =
-				api.get_mut_server(server_id).and_then(|s| s.get_mut_connection(connection_id))
+		} else {
+			// Connection switched channel, the cached channel is updated for all
+			// visibilities
+			// Add the connection if it entered visibility
+			if visibility == Visibility::Enter {
+				server_data!(api, server_id).add_connection(connection_id);
+			}
+			// Update the channel
+			server_data!(api, server_id).move_connection(connection_id, new_channel_id);
 			{
-				connection.channel_id = Ok(new_channel_id);
+				let server = api.get_server_unwrap(server_id);
+				let connection = server.get_connection_unwrap(connection_id);
+				let old_channel = server.get_channel_unwrap(old_channel_id);
+				let new_channel = server.get_channel_unwrap(new_channel_id);
+				log_move_event(api, &connection, old_channel_id, new_channel_id, &move_message);
+				plugin.connection_move(
+					api,
+					&server,
+					&connection,
+					&old_channel,
+					&new_channel,
+					visibility,
+				);
+				if server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id) {
+					plugin.own_connection_moved(api, &server, &old_channel, &new_channel, None);
+				}
+			}
+			// Remove the connection if it left visibility
+			if visibility == Visibility::Leave {
+				server_data!(api, server_id).remove_connection(connection_id);
 			}
 		}
-		{
-			let server = api.get_server_unwrap(server_id);
-			let connection = server.get_connection_unwrap(connection_id);
-			let old_channel = server.get_channel_unwrap(old_channel_id);
-			let new_channel = server.get_channel_unwrap(new_channel_id);
-			plugin.connection_move(
-				api,
-				&server,
-				&connection,
-				&old_channel,
-				&new_channel,
-				visibility,
-			);
-		}
-		// Remove the connection if it left visibility
-		if visibility == Visibility::Leave {
-			api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
-		}
-	}
+	})
 }
 
 #[allow(non_snake_case)]
@@ -356,89 +822,115 @@ pub unsafe extern "C" fn ts3plugin_onClientMoveMovedEvent(
 	visibility: c_int, invoker_id: u16, invoker_name: *const c_char, invoker_uid: *const c_char,
 	move_message: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let old_channel_id = ::ChannelId(old_channel_id);
-	let new_channel_id = ::ChannelId(new_channel_id);
-	let visibility = transmute(visibility);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let move_message = to_string!(move_message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	// Appart from the invoker, the same code as for ClientMove
-	api.try_update_invoker(server_id, &invoker);
-	if old_channel_id == ::ChannelId(0) {
-		// Connection connected, this will also be called for ourselves
-		api.get_mut_server(server_id).unwrap().add_connection(connection_id);
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		plugin.connection_changed(api, &server, &connection, true, move_message)
-	} else if new_channel_id == ::ChannelId(0) {
-		// Connection disconnected
-		{
+	catch_panic("ts3plugin_onClientMoveMovedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let old_channel_id = ::ChannelId(old_channel_id);
+		let new_channel_id = ::ChannelId(new_channel_id);
+		let visibility = transmute(visibility);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let move_message = to_string!(move_message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		// Appart from the invoker, the same code as for ClientMove
+		api.try_update_invoker(server_id, &invoker);
+		if old_channel_id == ::ChannelId(0) {
+			// Connection connected, this will also be called for ourselves
+			server_data!(api, server_id).add_connection(connection_id);
 			let server = api.get_server_unwrap(server_id);
 			let connection = server.get_connection_unwrap(connection_id);
-			plugin.connection_changed(api, &server, &connection, false, move_message);
-		}
-		api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
-	} else if old_channel_id == new_channel_id {
-		// Connection announced
-		match visibility {
-			Visibility::Enter => {
-				api.get_mut_server(server_id).unwrap().add_connection(connection_id);
+			let is_self = server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id);
+			log_move_event(api, &connection, old_channel_id, new_channel_id, &move_message);
+			plugin.connection_changed(api, &server, &connection, true, is_self, move_message)
+		} else if new_channel_id == ::ChannelId(0) {
+			// Connection disconnected
+			{
 				let server = api.get_server_unwrap(server_id);
 				let connection = server.get_connection_unwrap(connection_id);
-				plugin.connection_announced(api, &server, &connection, true);
+				let is_self = server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id);
+				log_move_event(api, &connection, old_channel_id, new_channel_id, &move_message);
+				plugin.connection_changed(api, &server, &connection, false, is_self, move_message);
 			}
-			Visibility::Leave => {
-				{
+			server_data!(api, server_id).remove_connection(connection_id);
+		} else if old_channel_id == new_channel_id {
+			// Connection announced
+			match visibility {
+				Visibility::Enter => {
+					server_data!(api, server_id).add_connection(connection_id);
 					let server = api.get_server_unwrap(server_id);
 					let connection = server.get_connection_unwrap(connection_id);
-					plugin.connection_announced(api, &server, &connection, false);
+					plugin.connection_announced(
+						api,
+						&server,
+						&connection,
+						true,
+						Some(&::Invoker::new(server.clone(), invoker)),
+					);
 				}
-				api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
+				Visibility::Leave => {
+					{
+						let server = api.get_server_unwrap(server_id);
+						let connection = server.get_connection_unwrap(connection_id);
+						plugin.connection_announced(
+							api,
+							&server,
+							&connection,
+							false,
+							Some(&::Invoker::new(server.clone(), invoker)),
+						);
+					}
+					server_data!(api, server_id).remove_connection(connection_id);
+				}
+				// The channel did not change, a move between different channels
+				// with retained visibility is a channel switch below
+				Visibility::Retain => {}
 			}
-			Visibility::Retain => {}
-		}
-	} else {
-		// Connection switched channel
-		// Add the connection if it entered visibility
-		if visibility == Visibility::Enter {
-			api.get_mut_server(server_id).unwrap().add_connection(connection_id);
-		}
-		// Update the channel
-		{
-			if let Some(connection) =
-				api.get_mut_server(server_id).and_then(|s| s.get_mut_connection(connection_id))
+		} else {
+			// Connection switched channel, the cached channel is updated for all
+			// visibilities
+			// Add the connection if it entered visibility
+			if visibility == Visibility::Enter {
+				server_data!(api, server_id).add_connection(connection_id);
+			}
+			// Update the channel
+			server_data!(api, server_id).move_connection(connection_id, new_channel_id);
 			{
-				connection.channel_id = Ok(new_channel_id);
+				let server = api.get_server_unwrap(server_id);
+				let connection = server.get_connection_unwrap(connection_id);
+				let old_channel = server.get_channel_unwrap(old_channel_id);
+				let new_channel = server.get_channel_unwrap(new_channel_id);
+				let invoker = ::Invoker::new(server.clone(), invoker);
+				log_move_event(api, &connection, old_channel_id, new_channel_id, &move_message);
+				plugin.connection_moved(
+					api,
+					&server,
+					&connection,
+					&old_channel,
+					&new_channel,
+					visibility,
+					&invoker,
+				);
+				if server.get_own_connection().map(|c| c.get_id()) == Ok(connection_id) {
+					plugin.own_connection_moved(
+						api,
+						&server,
+						&old_channel,
+						&new_channel,
+						Some(&invoker),
+					);
+				}
+			}
+			// Remove the connection if it left visibility
+			if visibility == Visibility::Leave {
+				api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
 			}
 		}
-		{
-			let server = api.get_server_unwrap(server_id);
-			let connection = server.get_connection_unwrap(connection_id);
-			let old_channel = server.get_channel_unwrap(old_channel_id);
-			let new_channel = server.get_channel_unwrap(new_channel_id);
-			plugin.connection_moved(
-				api,
-				&server,
-				&connection,
-				&old_channel,
-				&new_channel,
-				visibility,
-				&::Invoker::new(server.clone(), invoker),
-			);
-		}
-		// Remove the connection if it left visibility
-		if visibility == Visibility::Leave {
-			api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
-		}
-	}
+	})
 }
 
 #[allow(non_snake_case, unused_variables)]
@@ -447,33 +939,35 @@ pub unsafe extern "C" fn ts3plugin_onClientMoveMovedEvent(
 pub unsafe extern "C" fn ts3plugin_onClientMoveSubscriptionEvent(
 	server_id: u64, connection_id: u16, old_channel_id: u64, new_channel_id: u64, visibility: c_int,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	//let old_channel_id = ::ChannelId(old_channel_id);
-	//let new_channel_id = ::ChannelId(new_channel_id);
-	let visibility = transmute(visibility);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	// Connection announced
-	match visibility {
-		Visibility::Enter => {
-			api.get_mut_server(server_id).unwrap().add_connection(connection_id);
-			let server = api.get_server_unwrap(server_id);
-			let connection = server.get_connection_unwrap(connection_id);
-			plugin.connection_announced(api, &server, &connection, true);
-		}
-		Visibility::Leave => {
-			{
+	catch_panic("ts3plugin_onClientMoveSubscriptionEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		//let old_channel_id = ::ChannelId(old_channel_id);
+		//let new_channel_id = ::ChannelId(new_channel_id);
+		let visibility = transmute(visibility);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		// Connection announced
+		match visibility {
+			Visibility::Enter => {
+				server_data!(api, server_id).add_connection(connection_id);
 				let server = api.get_server_unwrap(server_id);
 				let connection = server.get_connection_unwrap(connection_id);
-				plugin.connection_announced(api, &server, &connection, false);
+				plugin.connection_announced(api, &server, &connection, true, None);
+			}
+			Visibility::Leave => {
+				{
+					let server = api.get_server_unwrap(server_id);
+					let connection = server.get_connection_unwrap(connection_id);
+					plugin.connection_announced(api, &server, &connection, false, None);
+				}
+				server_data!(api, server_id).remove_connection(connection_id);
 			}
-			api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
+			Visibility::Retain => {}
 		}
-		Visibility::Retain => {}
-	}
+	})
 }
 
 #[allow(non_snake_case, unused_variables)]
@@ -483,22 +977,24 @@ pub unsafe extern "C" fn ts3plugin_onClientMoveTimeoutEvent(
 	server_id: u64, connection_id: u16, old_channel_id: u64, new_channel_id: u64,
 	visibility: c_int, timeout_message: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	//let old_channel_id = ::ChannelId(old_channel_id);
-	//let new_channel_id = ::ChannelId(new_channel_id);
-	//let visibility = transmute(visibility);
-	let timeout_message = to_string!(timeout_message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	{
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		plugin.connection_timeout(api, &server, &connection);
-	}
-	api.get_mut_server(server_id).unwrap().remove_connection(connection_id);
+	catch_panic("ts3plugin_onClientMoveTimeoutEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		//let old_channel_id = ::ChannelId(old_channel_id);
+		//let new_channel_id = ::ChannelId(new_channel_id);
+		//let visibility = transmute(visibility);
+		let timeout_message = to_string!(timeout_message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		{
+			let server = api.get_server_unwrap(server_id);
+			let connection = server.get_connection_unwrap(connection_id);
+			plugin.connection_timeout(api, &server, &connection);
+		}
+		server_data!(api, server_id).remove_connection(connection_id);
+	})
 }
 
 #[allow(non_snake_case, unused_variables)]
@@ -507,20 +1003,22 @@ pub unsafe extern "C" fn ts3plugin_onClientMoveTimeoutEvent(
 pub unsafe extern "C" fn ts3plugin_onNewChannelEvent(
 	server_id: u64, channel_id: u64, parent_channel_id: u64,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	//let parent_channel_id = ::ChannelId(parent_channel_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let err = api.get_mut_server(server_id).unwrap().add_channel(channel_id).err();
-	if let Some(error) = err {
-		error!(api, "Can't get channel information", error);
-	}
-	let server = api.get_server_unwrap(server_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.channel_announced(api, &server, &channel);
+	catch_panic("ts3plugin_onNewChannelEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		//let parent_channel_id = ::ChannelId(parent_channel_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let err = server_data!(api, server_id).add_channel(channel_id).err();
+		if let Some(error) = err {
+			error!(api, "Can't get channel information", error);
+		}
+		let server = api.get_server_unwrap(server_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.channel_announced(api, &server, &channel);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -529,60 +1027,62 @@ pub unsafe extern "C" fn ts3plugin_onNewChannelEvent(
 pub unsafe extern "C" fn ts3plugin_onChannelDescriptionUpdateEvent(
 	server_id: u64, channel_id: u64,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	// FIXME
-	/*// Seems like I really like constructions like that, I failed to do it simpler
-	// because I can't borrow api to print an error message in the inner part.
-	if let Err(error) = if let Some(channel) = api.get_mut_server(server_id)
-			.unwrap().get_mut_channel(channel_id) {
-			channel.optional_data.update_description();
-			channel.get_optional_data().get_description().map(|_| ())
+	catch_panic("ts3plugin_onChannelDescriptionUpdateEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Err(error) = if let Some(channel) =
+			api.get_mut_server(server_id).and_then(|s| s.get_mut_channel(channel_id))
+		{
+			channel.update_description();
+			channel.get_description().map(|_| ())
 		} else {
 			Ok(())
 		} {
-		error!(api, "Can't get channel description", error);
-	}*/
-	let server = api.get_server_unwrap(server_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.channel_description_updated(api, &server, &channel);
+			error!(api, "Can't get channel description", error);
+		}
+		let server = api.get_server_unwrap(server_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.channel_description_updated(api, &server, &channel);
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_onUpdateChannelEvent(server_id: u64, channel_id: u64) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let old_channel;
-	if let Err(error) = {
-		let server = api.get_mut_server(server_id).unwrap();
-		// Try to get the old channel
-		old_channel =
-			server.remove_channel(channel_id).unwrap_or(::ChannelData::new(server_id, channel_id));
-		match server.add_channel(channel_id) {
-			Ok(_) => {
-				let channel = server.get_mut_channel(channel_id).unwrap();
-				// Copy optional data from old channel
-				channel.update_from(&old_channel);
-				Ok(())
+	catch_panic("ts3plugin_onUpdateChannelEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let old_channel;
+		if let Err(error) = {
+			let server = server_data!(api, server_id);
+			// Try to get the old channel
+			old_channel = server
+				.remove_channel(channel_id)
+				.unwrap_or(::ChannelData::new(server_id, channel_id));
+			match server.add_channel(channel_id) {
+				Ok(channel) => {
+					// Copy optional data from old channel
+					channel.update_from(&old_channel);
+					Ok(())
+				}
+				Err(error) => Err(error),
 			}
-			Err(error) => Err(error),
+		} {
+			error!(api, "Can't get channel information", error);
 		}
-	} {
-		error!(api, "Can't get channel information", error);
-	}
-	let server = api.get_server_unwrap(server_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.channel_updated(api, &server, &channel, &::Channel::new(api, &old_channel));
+		let server = api.get_server_unwrap(server_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.channel_updated(api, &server, &channel, &::Channel::new(api, &old_channel));
+	})
 }
 
 #[allow(non_snake_case, unused_variables)]
@@ -592,42 +1092,47 @@ pub unsafe extern "C" fn ts3plugin_onNewChannelCreatedEvent(
 	server_id: u64, channel_id: u64, parent_channel_id: u64, invoker_id: u16,
 	invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let parent_channel_id = ::ChannelId(parent_channel_id);
-	let invoker = if invoker_id == 0 {
-		None
-	} else {
-		Some(::InvokerData::new(
-			::ConnectionId(invoker_id),
-			to_string!(invoker_uid),
-			to_string!(invoker_name),
-		))
-	};
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	if let Some(ref invoker) = invoker {
-		api.try_update_invoker(server_id, invoker);
-	}
-	if let Some(error) = match api.get_mut_server(server_id).unwrap().add_channel(channel_id) {
-		Ok(channel) => {
-			channel.parent_channel_id = Ok(parent_channel_id);
+	catch_panic("ts3plugin_onNewChannelCreatedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let parent_channel_id = ::ChannelId(parent_channel_id);
+		let invoker = if invoker_id == 0 {
 			None
+		} else {
+			Some(::InvokerData::new(
+				::ConnectionId(invoker_id),
+				to_string!(invoker_uid),
+				to_string!(invoker_name),
+			))
+		};
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Some(ref invoker) = invoker {
+			api.try_update_invoker(server_id, invoker);
 		}
-		Err(error) => Some(error),
-	} {
-		error!(api, "Can't get channel information", error);
-	}
-	let server = api.get_server_unwrap(server_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.channel_created(
-		api,
-		&server,
-		&channel,
-		invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
-	);
+		if let Some(error) = {
+			let server = server_data!(api, server_id);
+			match server.add_channel(channel_id) {
+				Ok(_) => {
+					server.set_channel_parent(channel_id, parent_channel_id);
+					None
+				}
+				Err(error) => Some(error),
+			}
+		} {
+			error!(api, "Can't get channel information", error);
+		}
+		let server = api.get_server_unwrap(server_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.channel_created(
+			api,
+			&server,
+			&channel,
+			invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
+		);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -637,37 +1142,39 @@ pub unsafe extern "C" fn ts3plugin_onDelChannelEvent(
 	server_id: u64, channel_id: u64, invoker_id: u16, invoker_name: *const c_char,
 	invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let invoker = if invoker_id == 0 {
-		None
-	} else {
-		Some(::InvokerData::new(
-			::ConnectionId(invoker_id),
-			to_string!(invoker_uid),
-			to_string!(invoker_name),
-		))
-	};
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	if let Some(ref invoker) = invoker {
-		api.try_update_invoker(server_id, invoker);
-	}
-	{
-		let server = api.get_server_unwrap(server_id);
-		let channel = server.get_channel_unwrap(channel_id);
-		plugin.channel_deleted(
-			api,
-			&server,
-			&channel,
-			invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
-		);
-	}
-	if api.get_mut_server(server_id).and_then(|s| s.remove_channel(channel_id)).is_none() {
-		api.log_or_print("Can't remove channel", "rust-ts3plugin", ::LogLevel::Error);
-	}
+	catch_panic("ts3plugin_onDelChannelEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let invoker = if invoker_id == 0 {
+			None
+		} else {
+			Some(::InvokerData::new(
+				::ConnectionId(invoker_id),
+				to_string!(invoker_uid),
+				to_string!(invoker_name),
+			))
+		};
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Some(ref invoker) = invoker {
+			api.try_update_invoker(server_id, invoker);
+		}
+		{
+			let server = api.get_server_unwrap(server_id);
+			let channel = server.get_channel_unwrap(channel_id);
+			plugin.channel_deleted(
+				api,
+				&server,
+				&channel,
+				invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
+			);
+		}
+		if api.get_mut_server(server_id).and_then(|s| s.delete_channel(channel_id)).is_none() {
+			api.log_or_print("Can't remove channel", "rust-ts3plugin", ::LogLevel::Error);
+		}
+	})
 }
 
 #[allow(non_snake_case)]
@@ -677,59 +1184,63 @@ pub unsafe extern "C" fn ts3plugin_onUpdateChannelEditedEvent(
 	server_id: u64, channel_id: u64, invoker_id: u16, invoker_name: *const c_char,
 	invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	let old_channel;
-	if let Err(error) = {
-		let server = api.get_mut_server(server_id).unwrap();
-		// Try to get the old channel
-		old_channel =
-			server.remove_channel(channel_id).unwrap_or(::ChannelData::new(server_id, channel_id));
-		match server.add_channel(channel_id) {
-			Ok(_) => {
-				let channel = server.get_mut_channel(channel_id).unwrap();
-				// Copy optional data from old channel
-				channel.update_from(&old_channel);
-				Ok(())
+	catch_panic("ts3plugin_onUpdateChannelEditedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		let old_channel;
+		if let Err(error) = {
+			let server = server_data!(api, server_id);
+			// Try to get the old channel
+			old_channel = server
+				.remove_channel(channel_id)
+				.unwrap_or(::ChannelData::new(server_id, channel_id));
+			match server.add_channel(channel_id) {
+				Ok(channel) => {
+					// Copy optional data from old channel
+					channel.update_from(&old_channel);
+					Ok(())
+				}
+				Err(error) => Err(error),
 			}
-			Err(error) => Err(error),
+		} {
+			error!(api, "Can't get channel information", error);
 		}
-	} {
-		error!(api, "Can't get channel information", error);
-	}
-	let server = api.get_server_unwrap(server_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.channel_edited(
-		api,
-		&server,
-		&channel,
-		&::Channel::new(api, &old_channel),
-		&::Invoker::new(server.clone(), invoker),
-	);
+		let server = api.get_server_unwrap(server_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.channel_edited(
+			api,
+			&server,
+			&channel,
+			&::Channel::new(api, &old_channel),
+			&::Invoker::new(server.clone(), invoker),
+		);
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_onChannelPasswordChangedEvent(server_id: u64, channel_id: u64) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.channel_password_updated(api, &server, &channel);
+	catch_panic("ts3plugin_onChannelPasswordChangedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.channel_password_updated(api, &server, &channel);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -739,44 +1250,196 @@ pub unsafe extern "C" fn ts3plugin_onChannelMoveEvent(
 	server_id: u64, channel_id: u64, new_parent_channel_id: u64, invoker_id: u16,
 	invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_id = ::ChannelId(channel_id);
-	let new_parent_channel_id = ::ChannelId(new_parent_channel_id);
-	let invoker = if invoker_id == 0 {
-		None
-	} else {
-		Some(::InvokerData::new(
-			::ConnectionId(invoker_id),
-			to_string!(invoker_uid),
-			to_string!(invoker_name),
-		))
-	};
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	if let Some(ref invoker) = invoker {
-		api.try_update_invoker(server_id, invoker);
-	}
-	{
+	catch_panic("ts3plugin_onChannelMoveEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let new_parent_channel_id = ::ChannelId(new_parent_channel_id);
+		let invoker = if invoker_id == 0 {
+			None
+		} else {
+			Some(::InvokerData::new(
+				::ConnectionId(invoker_id),
+				to_string!(invoker_uid),
+				to_string!(invoker_name),
+			))
+		};
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Some(ref invoker) = invoker {
+			api.try_update_invoker(server_id, invoker);
+		}
+		{
+			let server = api.get_server_unwrap(server_id);
+			let channel = server.get_channel_unwrap(channel_id);
+			let new_parent_channel = server.get_channel_unwrap(new_parent_channel_id);
+			plugin.channel_moved(
+				api,
+				&server,
+				&channel,
+				&new_parent_channel,
+				invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
+			);
+		}
+		if let Some(server) = api.get_mut_server(server_id) {
+			server.set_channel_parent(channel_id, new_parent_channel_id);
+		}
+	})
+}
+
+// Ignore clippy warnings, we can't change the TeamSpeak interface
+#[allow(non_snake_case, unused_variables)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onChannelGroupListEvent(
+	server_id: u64, channel_group_id: u64, name: *const c_char, group_type: c_int, icon_id: c_int,
+	save_db: c_int,
+) {
+	catch_panic("ts3plugin_onChannelGroupListEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_group_id = ::ChannelGroupId(channel_group_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		if let Some(server) = api.get_mut_server(server_id) {
+			server.new_channel_groups.insert(
+				channel_group_id,
+				::ChannelGroup::new(channel_group_id, to_string!(name), icon_id),
+			);
+		}
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onChannelGroupListFinishedEvent(server_id: u64) {
+	catch_panic("ts3plugin_onChannelGroupListFinishedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		if let Some(server) = api.get_mut_server(server_id) {
+			server.channel_groups = std::mem::take(&mut server.new_channel_groups);
+			server.channel_group_list_requested.set(false);
+		}
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onChannelClientPermListEvent(
+	server_id: u64, channel_id: u64, database_id: u64, permission_id: c_uint,
+	permission_value: c_int, permission_negated: c_int, permission_skip: c_int,
+) {
+	catch_panic("ts3plugin_onChannelClientPermListEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let permission = ::ChannelClientPermission::new(
+			::PermissionId(permission_id),
+			permission_value,
+			permission_negated != 0,
+			permission_skip != 0,
+		);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		if let Some(server) = api.get_mut_server(server_id) {
+			server
+				.new_channel_client_permissions
+				.entry((channel_id, database_id))
+				.or_default()
+				.push(permission);
+		}
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onChannelClientPermListFinishedEvent(
+	server_id: u64, channel_id: u64, database_id: u64,
+) {
+	catch_panic("ts3plugin_onChannelClientPermListFinishedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let key = (channel_id, database_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Some(server) = api.get_mut_server(server_id) {
+			let permissions =
+				server.new_channel_client_permissions.remove(&key).unwrap_or_default();
+			server.channel_client_permissions.insert(key, permissions);
+		}
 		let server = api.get_server_unwrap(server_id);
 		let channel = server.get_channel_unwrap(channel_id);
-		let new_parent_channel = server.get_channel_unwrap(new_parent_channel_id);
-		plugin.channel_moved(
+		let permissions = server.get_channel_client_permissions(channel_id, database_id);
+		plugin.channel_client_permissions(
 			api,
 			&server,
 			&channel,
-			&new_parent_channel,
-			invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
+			database_id,
+			permissions.unwrap_or(&[]),
 		);
-	}
-	if let Some(channel) = api.get_mut_server(server_id).and_then(|s| s.get_mut_channel(channel_id))
-	{
-		channel.parent_channel_id = Ok(new_parent_channel_id);
-	}
+	})
+}
+
+#[allow(clippy::too_many_arguments)]
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onFileListEvent(
+	server_id: u64, channel_id: u64, path: *const c_char, name: *const c_char, size: u64,
+	datetime: u64, file_type: c_int, incomplete_size: u64, _return_code: *const c_char,
+) {
+	catch_panic("ts3plugin_onFileListEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let path = to_string!(path);
+		let entry = ::FileEntry {
+			name: to_string!(name),
+			size,
+			datetime: ::time::timestamp_from_secs(datetime).unwrap_or_default(),
+			file_type: if file_type == 0 { FileListType::Directory } else { FileListType::File },
+			incomplete_size,
+		};
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		if let Some(server) = api.get_mut_server(server_id) {
+			server.file_lists.borrow_mut().entry((channel_id, path)).or_default().push(entry);
+		}
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onFileListFinishedEvent(
+	server_id: u64, channel_id: u64, path: *const c_char,
+) {
+	catch_panic("ts3plugin_onFileListFinishedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_id = ::ChannelId(channel_id);
+		let path = to_string!(path);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let entries = match server.data {
+			Ok(data) => data.file_lists.borrow_mut().remove(&(channel_id, path.clone())),
+			Err(_) => None,
+		};
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.file_list(api, &server, &channel, &path, &entries.unwrap_or_default());
+	})
 }
 
-// Ignore clippy warnings, we can't change the TeamSpeak interface
 #[allow(clippy::too_many_arguments)]
 #[allow(non_snake_case)]
 #[no_mangle]
@@ -786,46 +1449,73 @@ pub unsafe extern "C" fn ts3plugin_onTextMessageEvent(
 	invoker_name: *const c_char, invoker_uid: *const c_char, message: *const c_char,
 	ignored: c_int,
 ) -> c_int {
-	let server_id = ::ServerId(server_id);
-	let target_mode = transmute(target_mode as i32);
-	let receiver_id = ::ConnectionId(receiver_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let message = to_string!(message);
-	let ignored = ignored != 0;
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	let message_receiver = match target_mode {
-		::TextMessageTargetMode::Client => ::MessageReceiver::Connection(receiver_id),
-		::TextMessageTargetMode::Channel => ::MessageReceiver::Channel,
-		::TextMessageTargetMode::Server => ::MessageReceiver::Server,
-		_ => {
-			api.log_or_print(
-				"Got invalid TextMessageTargetMode",
-				"rust-ts3plugin",
-				::LogLevel::Error,
-			);
-			::MessageReceiver::Server
+	catch_panic("ts3plugin_onTextMessageEvent", 0, || {
+		let server_id = ::ServerId(server_id);
+		let target_mode = transmute(target_mode as i32);
+		let receiver_id = ::ConnectionId(receiver_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let message = to_string!(message);
+		let ignored = ignored != 0;
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data, 0);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		let message_receiver = match target_mode {
+			::TextMessageTargetMode::Client => ::MessageReceiver::Connection(receiver_id),
+			::TextMessageTargetMode::Channel => ::MessageReceiver::Channel,
+			::TextMessageTargetMode::Server => ::MessageReceiver::Server,
+			_ => {
+				api.log_or_print(
+					"Got invalid TextMessageTargetMode",
+					"rust-ts3plugin",
+					::LogLevel::Error,
+				);
+				::MessageReceiver::Server
+			}
+		};
+		let server = api.get_server_unwrap(server_id);
+		let invoker = ::Invoker::new(server.clone(), invoker);
+		api.log_event(::Event::Message {
+			server_id,
+			invoker_id: invoker.get_id(),
+			invoker_name: invoker.get_name().to_string(),
+			receiver: message_receiver.clone(),
+			message: message.clone(),
+		});
+		let mut changed_message = message.clone();
+		if plugin.message_mut(
+			api,
+			&server,
+			&invoker,
+			message_receiver.clone(),
+			&mut changed_message,
+			ignored,
+		) || plugin.message(
+			api,
+			&server,
+			&invoker,
+			message_receiver.clone(),
+			changed_message.clone(),
+			ignored,
+		) {
+			1
+		} else if changed_message != message {
+			// Hide the original message and show the changed one instead
+			let text = format!("\"{}\": {}", invoker.get_name(), changed_message);
+			match message_receiver {
+				::MessageReceiver::Connection(_) => api.print_message(text),
+				::MessageReceiver::Channel => server.print_message(text, ::MessageTarget::Channel),
+				::MessageReceiver::Server => server.print_message(text, ::MessageTarget::Server),
+			}
+			1
+		} else {
+			0
 		}
-	};
-	let server = api.get_server_unwrap(server_id);
-	if plugin.message(
-		api,
-		&server,
-		&::Invoker::new(server.clone(), invoker),
-		message_receiver,
-		message,
-		ignored,
-	) {
-		1
-	} else {
-		0
-	}
+	})
 }
 
 #[allow(non_snake_case)]
@@ -835,24 +1525,26 @@ pub unsafe extern "C" fn ts3plugin_onClientPokeEvent(
 	server_id: u64, invoker_id: u16, invoker_name: *const c_char, invoker_uid: *const c_char,
 	message: *const c_char, ignored: c_int,
 ) -> c_int {
-	let server_id = ::ServerId(server_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let message = to_string!(message);
-	let ignored = ignored != 0;
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	let server = api.get_server_unwrap(server_id);
-	if plugin.poke(api, &server, &::Invoker::new(server.clone(), invoker), message, ignored) {
-		1
-	} else {
-		0
-	}
+	catch_panic("ts3plugin_onClientPokeEvent", 0, || {
+		let server_id = ::ServerId(server_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let message = to_string!(message);
+		let ignored = ignored != 0;
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data, 0);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		let server = api.get_server_unwrap(server_id);
+		if plugin.poke(api, &server, &::Invoker::new(server.clone(), invoker), message, ignored) {
+			1
+		} else {
+			0
+		}
+	})
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -864,46 +1556,48 @@ pub unsafe extern "C" fn ts3plugin_onClientKickFromChannelEvent(
 	visibility: c_int, invoker_id: u16, invoker_name: *const c_char, invoker_uid: *const c_char,
 	message: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let old_channel_id = ::ChannelId(old_channel_id);
-	let new_channel_id = ::ChannelId(new_channel_id);
-	let visibility = transmute(visibility);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let message = to_string!(message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	{
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		let old_channel = server.get_channel_unwrap(old_channel_id);
-		let new_channel = server.get_channel_unwrap(new_channel_id);
-		plugin.channel_kick(
-			api,
-			&server,
-			&connection,
-			&old_channel,
-			&new_channel,
-			visibility,
-			&::Invoker::new(server.clone(), invoker),
-			message,
-		);
-	}
-	// Remove the kicked connection if it is not visible anymore
-	if visibility == ::Visibility::Leave {
-		api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
-	} else if let Some(connection) = api.get_mut_server(server_id).and_then(|s|
-		// Update the current channel of the connection
-		s.get_mut_connection(connection_id))
-	{
-		connection.channel_id = Ok(new_channel_id);
-	}
+	catch_panic("ts3plugin_onClientKickFromChannelEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let old_channel_id = ::ChannelId(old_channel_id);
+		let new_channel_id = ::ChannelId(new_channel_id);
+		let visibility = transmute(visibility);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let message = to_string!(message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		{
+			let server = api.get_server_unwrap(server_id);
+			let connection = server.get_connection_unwrap(connection_id);
+			let old_channel = server.get_channel_unwrap(old_channel_id);
+			let new_channel = server.get_channel_unwrap(new_channel_id);
+			plugin.channel_kick(
+				api,
+				&server,
+				&connection,
+				&old_channel,
+				&new_channel,
+				visibility,
+				&::Invoker::new(server.clone(), invoker),
+				message,
+			);
+		}
+		// Remove the kicked connection if it is not visible anymore
+		if visibility == ::Visibility::Leave {
+			api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
+		} else if let Some(connection) = api.get_mut_server(server_id).and_then(|s|
+			// Update the current channel of the connection
+			s.get_mut_connection(connection_id))
+		{
+			connection.channel_id = Ok(new_channel_id);
+		}
+	})
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -915,34 +1609,36 @@ pub unsafe extern "C" fn ts3plugin_onClientKickFromServerEvent(
 	visibility: c_int, invoker_id: u16, invoker_name: *const c_char, invoker_uid: *const c_char,
 	message: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let old_channel_id = ::ChannelId(old_channel_id);
-	let new_channel_id = ::ChannelId(new_channel_id);
-	//let visibility = transmute(visibility);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let message = to_string!(message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	{
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		plugin.server_kick(
-			api,
-			&server,
-			&connection,
-			&::Invoker::new(server.clone(), invoker),
-			message,
-		);
-	}
-	// Remove the kicked connection
-	api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
+	catch_panic("ts3plugin_onClientKickFromServerEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let old_channel_id = ::ChannelId(old_channel_id);
+		let new_channel_id = ::ChannelId(new_channel_id);
+		//let visibility = transmute(visibility);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let message = to_string!(message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		{
+			let server = api.get_server_unwrap(server_id);
+			let connection = server.get_connection_unwrap(connection_id);
+			plugin.server_kick(
+				api,
+				&server,
+				&connection,
+				&::Invoker::new(server.clone(), invoker),
+				message,
+			);
+		}
+		// Remove the kicked connection
+		api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
+	})
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -954,35 +1650,37 @@ pub unsafe extern "C" fn ts3plugin_onClientBanFromServerEvent(
 	visibility: c_int, invoker_id: u16, invoker_name: *const c_char, invoker_uid: *const c_char,
 	time: u64, message: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	//let old_channel_id = ::ChannelId(old_channel_id);
-	//let new_channel_id = ::ChannelId(new_channel_id);
-	//let visibility = transmute(visibility);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let message = to_string!(message);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	{
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		plugin.server_ban(
-			api,
-			&server,
-			&connection,
-			&::Invoker::new(server.clone(), invoker),
-			message,
-			time,
-		);
-	}
-	// Remove the banned connection
-	api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
+	catch_panic("ts3plugin_onClientBanFromServerEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		//let old_channel_id = ::ChannelId(old_channel_id);
+		//let new_channel_id = ::ChannelId(new_channel_id);
+		//let visibility = transmute(visibility);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let message = to_string!(message);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		{
+			let server = api.get_server_unwrap(server_id);
+			let connection = server.get_connection_unwrap(connection_id);
+			plugin.server_ban(
+				api,
+				&server,
+				&connection,
+				&::Invoker::new(server.clone(), invoker),
+				message,
+				time,
+			);
+		}
+		// Remove the banned connection
+		api.get_mut_server(server_id).map(|s| s.remove_connection(connection_id));
+	})
 }
 
 #[allow(non_snake_case)]
@@ -991,26 +1689,33 @@ pub unsafe extern "C" fn ts3plugin_onClientBanFromServerEvent(
 pub unsafe extern "C" fn ts3plugin_onTalkStatusChangeEvent(
 	server_id: u64, talking: c_int, whispering: c_int, connection_id: u16,
 ) {
-	let server_id = ::ServerId(server_id);
-	let talking = transmute(talking);
-	let whispering = whispering != 0;
-	let connection_id = ::ConnectionId(connection_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	{
-		let server = api.get_server_unwrap(server_id);
-		let connection = server.get_connection_unwrap(connection_id);
-		plugin.talking_changed(api, &server, &connection, talking, whispering);
-	}
-	// Update the connection
-	if let Some(connection) =
-		api.get_mut_server(server_id).and_then(|s| s.get_mut_connection(connection_id))
-	{
-		connection.talking = Ok(talking);
-		connection.whispering = Ok(whispering);
-	}
+	catch_panic("ts3plugin_onTalkStatusChangeEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let talking = transmute(talking);
+		let whispering = whispering != 0;
+		let connection_id = ::ConnectionId(connection_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		{
+			let server = api.get_server_unwrap(server_id);
+			let connection = server.get_connection_unwrap(connection_id);
+			plugin.talking_changed(api, &server, &connection, talking, whispering);
+		}
+		// Update the connection
+		if let Some(connection) =
+			api.get_mut_server(server_id).and_then(|s| s.get_mut_connection(connection_id))
+		{
+			if talking != TalkStatus::Talking {
+				connection.talking_since = None;
+			} else if connection.talking_since.is_none() {
+				connection.talking_since = Some(::time::now());
+			}
+			connection.talking = Ok(talking);
+			connection.whispering = Ok(whispering);
+		}
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1019,17 +1724,52 @@ pub unsafe extern "C" fn ts3plugin_onTalkStatusChangeEvent(
 pub unsafe extern "C" fn ts3plugin_onAvatarUpdated(
 	server_id: u64, connection_id: u16, avatar_path: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let path = if avatar_path.is_null() { None } else { Some(to_string!(avatar_path)) };
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let connection = server.get_connection_unwrap(connection_id);
-	plugin.avatar_changed(api, &server, &connection, path);
+	catch_panic("ts3plugin_onAvatarUpdated", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		// The path is null if the avatar was removed
+		let path = if avatar_path.is_null() { None } else { Some(to_string!(avatar_path)) };
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let connection = server.get_connection_unwrap(connection_id);
+		match path {
+			Some(path) => plugin.avatar_changed(api, &server, &connection, path),
+			None => plugin.avatar_removed(api, &server, &connection),
+		}
+	})
+}
+
+#[allow(non_snake_case, unused_variables)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onClientDisplayNameChanged(
+	server_id: u64, connection_id: u16, display_name: *const c_char, uid: *const c_char,
+) {
+	catch_panic("ts3plugin_onClientDisplayNameChanged", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let display_name = to_string!(display_name);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		{
+			let server = api.get_server_unwrap(server_id);
+			let connection = server.get_connection_unwrap(connection_id);
+			plugin.client_display_name_changed(api, &server, &connection, display_name.clone());
+		}
+		// Update the connection
+		if let Some(connection) =
+			api.get_mut_server(server_id).and_then(|s| s.get_mut_connection(connection_id))
+		{
+			connection.display_name = Ok(display_name);
+		}
+	})
 }
+
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
@@ -1037,31 +1777,79 @@ pub unsafe extern "C" fn ts3plugin_onClientChannelGroupChangedEvent(
 	server_id: u64, channel_group_id: u64, channel_id: u64, connection_id: u16, invoker_id: u16,
 	invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let channel_group_id = ::ChannelGroupId(channel_group_id);
-	let channel_id = ::ChannelId(channel_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	let server = api.get_server_unwrap(server_id);
-	let connection = server.get_connection_unwrap(connection_id);
-	let channel_group = server.get_channel_group_unwrap(channel_group_id);
-	let channel = server.get_channel_unwrap(channel_id);
-	plugin.connection_channel_group_changed(
-		api,
-		&server,
-		&connection,
-		&channel_group,
-		&channel,
-		&::Invoker::new(server.clone(), invoker),
-	);
+	catch_panic("ts3plugin_onClientChannelGroupChangedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let channel_group_id = ::ChannelGroupId(channel_group_id);
+		let channel_id = ::ChannelId(channel_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		let server = api.get_server_unwrap(server_id);
+		let connection = server.get_connection_unwrap(connection_id);
+		let channel_group = server.get_channel_group_unwrap(channel_group_id);
+		let channel = server.get_channel_unwrap(channel_id);
+		plugin.connection_channel_group_changed(
+			api,
+			&server,
+			&connection,
+			&channel_group,
+			&channel,
+			&::Invoker::new(server.clone(), invoker),
+		);
+	})
+}
+
+#[allow(non_snake_case, unused_variables)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onServerGroupListEvent(
+	server_id: u64, server_group_id: u64, name: *const c_char, group_type: c_int, icon_id: c_int,
+	save_db: c_int,
+) {
+	catch_panic("ts3plugin_onServerGroupListEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let server_group_id = ::ServerGroupId(server_group_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		if let Some(server) = api.get_mut_server(server_id) {
+			server.new_server_groups.insert(
+				server_group_id,
+				::ServerGroup::new(server_group_id, to_string!(name), icon_id),
+			);
+		}
+	})
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+#[doc(hidden)]
+pub unsafe extern "C" fn ts3plugin_onServerGroupListFinishedEvent(server_id: u64) {
+	catch_panic("ts3plugin_onServerGroupListFinishedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let mut groups = match api.get_mut_server(server_id) {
+			Some(server) => {
+				server.server_groups = std::mem::take(&mut server.new_server_groups);
+				server.server_group_list_requested.set(false);
+				server.server_groups.values().cloned().collect::<Vec<_>>()
+			}
+			None => return,
+		};
+		groups.sort_by_key(|g| g.get_id());
+		let server = api.get_server_unwrap(server_id);
+		plugin.server_group_list_changed(api, &server, &groups);
+	})
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1073,30 +1861,32 @@ pub unsafe extern "C" fn ts3plugin_onServerGroupClientAddedEvent(
 	connection_uid: *const c_char, server_group_id: u64, invoker_id: u16,
 	invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let connection_name = to_string!(connection_name);
-	let connection_uid = to_string!(connection_uid);
-	let connection = ::InvokerData::new(connection_id, connection_uid, connection_name);
-	let server_group_id = ::ServerGroupId(server_group_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	let server = api.get_server_unwrap(server_id);
-	let server_group = server.get_server_group_unwrap(server_group_id);
-	plugin.connection_server_group_added(
-		api,
-		&server,
-		&::Invoker::new(server.clone(), connection),
-		&server_group,
-		&::Invoker::new(server.clone(), invoker),
-	);
+	catch_panic("ts3plugin_onServerGroupClientAddedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let connection_name = to_string!(connection_name);
+		let connection_uid = to_string!(connection_uid);
+		let connection = ::InvokerData::new(connection_id, connection_uid, connection_name);
+		let server_group_id = ::ServerGroupId(server_group_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		let server = api.get_server_unwrap(server_id);
+		let server_group = server.get_server_group_unwrap(server_group_id);
+		plugin.connection_server_group_added(
+			api,
+			&server,
+			&::Invoker::new(server.clone(), connection),
+			&server_group,
+			&::Invoker::new(server.clone(), invoker),
+		);
+	})
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1108,30 +1898,32 @@ pub unsafe extern "C" fn ts3plugin_onServerGroupClientDeletedEvent(
 	connection_uid: *const c_char, server_group_id: u64, invoker_id: u16,
 	invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let connection_name = to_string!(connection_name);
-	let connection_uid = to_string!(connection_uid);
-	let connection = ::InvokerData::new(connection_id, connection_uid, connection_name);
-	let server_group_id = ::ServerGroupId(server_group_id);
-	let invoker_id = ::ConnectionId(invoker_id);
-	let invoker_name = to_string!(invoker_name);
-	let invoker_uid = to_string!(invoker_uid);
-	let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	api.try_update_invoker(server_id, &invoker);
-	let server = api.get_server_unwrap(server_id);
-	let server_group = server.get_server_group_unwrap(server_group_id);
-	plugin.connection_server_group_removed(
-		api,
-		&server,
-		&::Invoker::new(server.clone(), connection),
-		&server_group,
-		&::Invoker::new(server.clone(), invoker),
-	);
+	catch_panic("ts3plugin_onServerGroupClientDeletedEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let connection_name = to_string!(connection_name);
+		let connection_uid = to_string!(connection_uid);
+		let connection = ::InvokerData::new(connection_id, connection_uid, connection_name);
+		let server_group_id = ::ServerGroupId(server_group_id);
+		let invoker_id = ::ConnectionId(invoker_id);
+		let invoker_name = to_string!(invoker_name);
+		let invoker_uid = to_string!(invoker_uid);
+		let invoker = ::InvokerData::new(invoker_id, invoker_uid, invoker_name);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		api.try_update_invoker(server_id, &invoker);
+		let server = api.get_server_unwrap(server_id);
+		let server_group = server.get_server_group_unwrap(server_group_id);
+		plugin.connection_server_group_removed(
+			api,
+			&server,
+			&::Invoker::new(server.clone(), connection),
+			&server_group,
+			&::Invoker::new(server.clone(), invoker),
+		);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1141,22 +1933,24 @@ pub unsafe extern "C" fn ts3plugin_onServerPermissionErrorEvent(
 	server_id: u64, message: *const c_char, error: c_uint, return_code: *const c_char,
 	permission_id: c_uint,
 ) -> c_int {
-	let server_id = ::ServerId(server_id);
-	let message = to_string!(message);
-	let error = transmute(error);
-	let return_code = to_string!(return_code);
-	let permission_id = ::PermissionId(permission_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let permission = api.get_permission(permission_id).unwrap();
-	if plugin.permission_error(api, &server, permission, error, message, return_code) {
-		1
-	} else {
-		0
-	}
+	catch_panic("ts3plugin_onServerPermissionErrorEvent", 0, || {
+		let server_id = ::ServerId(server_id);
+		let message = to_string!(message);
+		let error = transmute(error);
+		let return_code = to_string!(return_code);
+		let permission_id = ::PermissionId(permission_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data, 0);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let permission = api.get_permission(permission_id).unwrap();
+		if plugin.permission_error(api, &server, permission, error, message, return_code) {
+			1
+		} else {
+			0
+		}
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1165,16 +1959,21 @@ pub unsafe extern "C" fn ts3plugin_onServerPermissionErrorEvent(
 pub unsafe extern "C" fn ts3plugin_onEditPlaybackVoiceDataEvent(
 	server_id: u64, connection_id: u16, samples: *mut c_short, sample_count: c_int, channels: c_int,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let connection = server.get_connection_unwrap(connection_id);
-	plugin.playback_voice_data(api, &server, &connection, samples, channels);
+	catch_panic("ts3plugin_onEditPlaybackVoiceDataEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
+		if buffer_audio(server_id, Some(connection_id), samples, channels) {
+			return;
+		}
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let connection = server.get_connection_unwrap(connection_id);
+		plugin.playback_voice_data(api, &server, &connection, samples, channels);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1184,27 +1983,29 @@ pub unsafe extern "C" fn ts3plugin_onEditPostProcessVoiceDataEvent(
 	server_id: u64, connection_id: u16, samples: *mut c_short, sample_count: c_int,
 	channels: c_int, channel_speaker_array: *const c_uint, channel_fill_mask: *mut c_uint,
 ) {
-	let server_id = ::ServerId(server_id);
-	let connection_id = ::ConnectionId(connection_id);
-	let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
-	let channel_speaker_array =
-		slice::from_raw_parts(channel_speaker_array as *mut ::Speaker, channels as usize);
-	let channel_fill_mask = channel_fill_mask.as_mut().unwrap();
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	let connection = server.get_connection_unwrap(connection_id);
-	plugin.post_process_voice_data(
-		api,
-		&server,
-		&connection,
-		samples,
-		channels,
-		channel_speaker_array,
-		channel_fill_mask,
-	);
+	catch_panic("ts3plugin_onEditPostProcessVoiceDataEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let connection_id = ::ConnectionId(connection_id);
+		let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
+		let channel_speaker_array =
+			slice::from_raw_parts(channel_speaker_array as *mut ::Speaker, channels as usize);
+		let channel_fill_mask = channel_fill_mask.as_mut().unwrap();
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		let connection = server.get_connection_unwrap(connection_id);
+		plugin.post_process_voice_data(
+			api,
+			&server,
+			&connection,
+			samples,
+			channels,
+			channel_speaker_array,
+			channel_fill_mask,
+		);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1214,24 +2015,26 @@ pub unsafe extern "C" fn ts3plugin_onEditMixedPlaybackVoiceDataEvent(
 	server_id: u64, samples: *mut c_short, sample_count: c_int, channels: c_int,
 	channel_speaker_array: *const c_uint, channel_fill_mask: *mut c_uint,
 ) {
-	let server_id = ::ServerId(server_id);
-	let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
-	let channel_speaker_array =
-		slice::from_raw_parts(channel_speaker_array as *mut ::Speaker, channels as usize);
-	let channel_fill_mask = channel_fill_mask.as_mut().unwrap();
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	plugin.mixed_playback_voice_data(
-		api,
-		&server,
-		samples,
-		channels,
-		channel_speaker_array,
-		channel_fill_mask,
-	);
+	catch_panic("ts3plugin_onEditMixedPlaybackVoiceDataEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
+		let channel_speaker_array =
+			slice::from_raw_parts(channel_speaker_array as *mut ::Speaker, channels as usize);
+		let channel_fill_mask = channel_fill_mask.as_mut().unwrap();
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		plugin.mixed_playback_voice_data(
+			api,
+			&server,
+			samples,
+			channels,
+			channel_speaker_array,
+			channel_fill_mask,
+		);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1240,18 +2043,23 @@ pub unsafe extern "C" fn ts3plugin_onEditMixedPlaybackVoiceDataEvent(
 pub unsafe extern "C" fn ts3plugin_onEditCapturedVoiceDataEvent(
 	server_id: u64, samples: *mut c_short, sample_count: c_int, channels: c_int, edited: *mut c_int,
 ) {
-	let server_id = ::ServerId(server_id);
-	let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
-	let mut send = (*edited & 2) != 0;
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	// Set the first bit if the sound data were edited
-	*edited |= plugin.captured_voice_data(api, &server, samples, channels, &mut send) as c_int;
-	// Set the second bit of `edited` to `send`
-	*edited = (*edited & !2) | ((send as c_int) << 1);
+	catch_panic("ts3plugin_onEditCapturedVoiceDataEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let samples = slice::from_raw_parts_mut(samples, (sample_count * channels) as usize);
+		if buffer_audio(server_id, None, samples, channels) {
+			return;
+		}
+		let mut send = (*edited & 2) != 0;
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		// Set the first bit if the sound data were edited
+		*edited |= plugin.captured_voice_data(api, &server, samples, channels, &mut send) as c_int;
+		// Set the second bit of `edited` to `send`
+		*edited = (*edited & !2) | ((send as c_int) << 1);
+	})
 }
 
 #[allow(non_snake_case)]
@@ -1261,42 +2069,46 @@ pub unsafe extern "C" fn ts3plugin_onPluginCommandEvent(
 	server_id: u64, plugin_name: *const c_char, plugin_command: *const c_char, invoker_id: u16,
 	invoker_name: *const c_char, invoker_uid: *const c_char,
 ) {
-	let server_id = ::ServerId(server_id);
-	let invoker = if invoker_id == 0 {
-		None
-	} else {
-		Some(::InvokerData::new(
-			::ConnectionId(invoker_id),
-			to_string!(invoker_uid),
-			to_string!(invoker_name),
-		))
-	};
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	if let Some(ref invoker) = invoker {
-		api.try_update_invoker(server_id, invoker);
-	}
-	let server = api.get_server_unwrap(server_id);
-	plugin.plugin_message(
-		api,
-		&server,
-		to_string!(plugin_name),
-		to_string!(plugin_command),
-		invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
-	);
+	catch_panic("ts3plugin_onPluginCommandEvent", (), || {
+		let server_id = ::ServerId(server_id);
+		let invoker = if invoker_id == 0 {
+			None
+		} else {
+			Some(::InvokerData::new(
+				::ConnectionId(invoker_id),
+				to_string!(invoker_uid),
+				to_string!(invoker_name),
+			))
+		};
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		if let Some(ref invoker) = invoker {
+			api.try_update_invoker(server_id, invoker);
+		}
+		let server = api.get_server_unwrap(server_id);
+		plugin.plugin_message(
+			api,
+			&server,
+			to_string!(plugin_name),
+			to_string!(plugin_command),
+			invoker.map(|i| ::Invoker::new(server.clone(), i)).as_ref(),
+		);
+	})
 }
 
 #[allow(non_snake_case)]
 #[no_mangle]
 #[doc(hidden)]
 pub unsafe extern "C" fn ts3plugin_processCommand(server_id: u64, command: *const c_char) -> c_int {
-	let server_id = ::ServerId(server_id);
-	let mut data = DATA.lock().unwrap();
-	let data = data.0.as_mut().unwrap();
-	let api = &mut data.0;
-	let plugin = &mut data.1;
-	let server = api.get_server_unwrap(server_id);
-	if plugin.process_command(api, &server, to_string!(command)) { 0 } else { 1 }
+	catch_panic("ts3plugin_processCommand", 1, || {
+		let server_id = ::ServerId(server_id);
+		let mut data = DATA.lock().unwrap();
+		let data = plugin_data!(data, 1);
+		let api = &mut data.0;
+		let plugin = &mut data.1;
+		let server = api.get_server_unwrap(server_id);
+		if plugin.process_command(api, &server, to_string!(command)) { 0 } else { 1 }
+	})
 }